@@ -11,11 +11,17 @@ use bevy::{
   DefaultPlugins,
 };
 use bevy_ecs_ldtk::LdtkPlugin;
+use bevy_egui::EguiPlugin;
 use bevy_light_2d::plugin::Light2dPlugin;
 use plugins::{
-  camera::CameraPlugin, controls::ControlsPlugin, daycycle::DayCyclePlugin,
-  gen::WorldGenPlugin, grass::GrassPlugin, housing::HousingPlugin,
-  interface::InterfacePlugin, player::PlayerPlugin, tools::ToolsPlugin,
+  camera::CameraPlugin, controls::ControlsPlugin, crafting::CraftingPlugin,
+  crops::CropsPlugin, daycycle::DayCyclePlugin, drops::DropsPlugin,
+  energy::EnergyPlugin, gen::WorldGenPlugin, grass::GrassPlugin,
+  housing::HousingPlugin, interface::InterfacePlugin,
+  inventory::InventoryPlugin, machines::MachinesPlugin, market::MarketPlugin,
+  player::PlayerPlugin, quests::QuestsPlugin, shipping::ShippingPlugin,
+  shop::ShopPlugin, soil::SoilPlugin, sprinkler::SprinklerPlugin,
+  tools::ToolsPlugin, wallet::WalletPlugin, weather::WeatherPlugin,
   world::WorldPlugin,
 };
 
@@ -36,6 +42,7 @@ fn main() -> AppExit {
     .add_plugins(LdtkPlugin)
     .add_plugins(PhysicsPlugins::default().with_length_unit(16.0))
     .add_plugins(Light2dPlugin)
+    .add_plugins(EguiPlugin)
     .add_plugins(PlayerPlugin)
     .add_plugins(ToolsPlugin)
     .add_plugins(ControlsPlugin)
@@ -46,6 +53,20 @@ fn main() -> AppExit {
     .add_plugins(WorldGenPlugin)
     .add_plugins(InterfacePlugin)
     .add_plugins(DayCyclePlugin)
+    .add_plugins(InventoryPlugin)
+    .add_plugins(CropsPlugin)
+    .add_plugins(SoilPlugin)
+    .add_plugins(SprinklerPlugin)
+    .add_plugins(EnergyPlugin)
+    .add_plugins(WalletPlugin)
+    .add_plugins(ShippingPlugin)
+    .add_plugins(ShopPlugin)
+    .add_plugins(CraftingPlugin)
+    .add_plugins(DropsPlugin)
+    .add_plugins(MachinesPlugin)
+    .add_plugins(MarketPlugin)
+    .add_plugins(QuestsPlugin)
+    .add_plugins(WeatherPlugin)
     .insert_resource(Gravity(Vec2::ZERO))
     .run()
 }
@@ -1,12 +1,26 @@
 pub mod camera;
 pub mod controls;
+pub mod crafting;
+pub mod crops;
 pub mod daycycle;
 pub mod debug;
+pub mod drops;
+pub mod energy;
 pub mod gen;
 pub mod grass;
 pub mod housing;
 pub mod interface;
+pub mod inventory;
+pub mod machines;
+pub mod market;
 pub mod player;
+pub mod quests;
 pub mod road;
+pub mod shipping;
+pub mod shop;
+pub mod soil;
+pub mod sprinkler;
 pub mod tools;
+pub mod wallet;
+pub mod weather;
 pub mod world;
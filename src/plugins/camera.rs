@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use super::{daycycle::DayCycle, player::Player};
+use super::{daycycle::DayCycle, player::Player, weather::Forecast};
 use bevy::{
   app::{App, Plugin, Startup, Update},
   math::Vec3,
@@ -34,6 +34,7 @@ fn setup_camera(mut commands: Commands) {
 fn update(
   time: Res<Time>,
   day: Res<DayCycle>,
+  forecast: Res<Forecast>,
   mut camera: Query<
     (&mut Transform, &mut AmbientLight2d),
     (With<Camera2d>, Without<Player>),
@@ -44,7 +45,8 @@ fn update(
     return;
   };
 
-  sunlight.brightness = sunlight_brightness(day.daytime);
+  sunlight.brightness =
+    sunlight_brightness(day.daytime) * forecast.today.light() + forecast.flash;
 
   let Ok(player) = player.get_single() else {
     return;
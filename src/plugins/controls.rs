@@ -1,10 +1,11 @@
+use super::player::ToolUse;
 use crate::components::physics::{Acceleration, PhysicsBundle};
 use avian2d::prelude::LinearVelocity;
 use bevy::{
   app::{App, Plugin, Update},
   input::ButtonInput,
   math::Vec2,
-  prelude::{Bundle, Component, KeyCode, Query, Res, With},
+  prelude::{Bundle, Component, KeyCode, Query, Res, With, Without},
   time::Time,
 };
 
@@ -30,7 +31,7 @@ pub enum Direction {
 fn input(
   mut q_target: Query<
     (&mut LinearVelocity, &Acceleration, &mut Direction),
-    With<PhysicsControls>,
+    (With<PhysicsControls>, Without<ToolUse>),
   >,
   time: Res<Time>,
   kbd: Res<ButtonInput<KeyCode>>,
@@ -51,7 +52,9 @@ fn input(
   }
 
   for (mut vel, acceleration, mut dir) in &mut q_target {
-    *dir = Direction::from(direction);
+    if direction != Vec2::ZERO {
+      *dir = Direction::from(direction);
+    }
     let delta_velocity =
       direction.normalize_or_zero() * acceleration.0 * time.delta_seconds();
     vel.0 += delta_velocity;
@@ -59,6 +62,26 @@ fn input(
 }
 
 impl Direction {
+  pub const fn offset(&self) -> (i32, i32) {
+    match self {
+      Direction::Up => (0, 1),
+      Direction::Down => (0, -1),
+      Direction::Left => (-1, 0),
+      Direction::Right => (1, 0),
+    }
+  }
+
+  // Turns toward `delta`, along whichever axis it leans on most
+  pub fn towards(delta: Vec2) -> Option<Self> {
+    if delta == Vec2::ZERO {
+      None
+    } else if delta.x.abs() > delta.y.abs() {
+      Some(Direction::from(Vec2::new(delta.x, 0.)))
+    } else {
+      Some(Direction::from(Vec2::new(0., delta.y)))
+    }
+  }
+
   pub const fn atlas_index(&self) -> usize {
     match self {
       Direction::Up => 2,
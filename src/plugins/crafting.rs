@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+  app::{App, Plugin, Startup, Update},
+  asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets,
+    AsyncReadExt, Handle, LoadContext,
+  },
+  input::ButtonInput,
+  prelude::{
+    Commands, EventReader, EventWriter, KeyCode, Res, ResMut, Resource,
+  },
+  reflect::TypePath,
+};
+use bevy_egui::{egui, EguiContexts};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+  interface::Notice,
+  inventory::{Inventory, Item, ItemAmount, ItemData},
+  tools::{HarvestEvent, Tool, ToolTier, ToolTiers},
+  wallet::{Trader, Wallet},
+};
+
+#[derive(Deserialize)]
+pub enum RecipeOutput {
+  Item(ItemAmount),
+  // Raises a tool to the given tier, which must be the next one
+  Upgrade(Tool, ToolTier),
+}
+
+#[derive(Default, Deserialize)]
+pub enum Unlock {
+  #[default]
+  Start,
+  // Total amount of an item harvested so far
+  Harvested(Item, u32),
+  // Total money earned so far
+  Earned(u32),
+}
+
+#[derive(Deserialize)]
+pub struct Recipe {
+  pub name: String,
+  pub inputs: Vec<ItemAmount>,
+  // Money charged on top of the inputs
+  #[serde(default)]
+  pub cost: u32,
+  pub output: RecipeOutput,
+  #[serde(default)]
+  pub unlock: Unlock,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct RecipeBook {
+  recipes: Vec<Recipe>,
+}
+
+#[derive(Resource)]
+struct Crafting {
+  book: Handle<RecipeBook>,
+  // Names of the recipes unlocked so far
+  unlocked: HashSet<String>,
+  open: bool,
+}
+
+// What the player achieved so far, which unlocks recipes
+#[derive(Resource, Default)]
+pub struct Progress {
+  harvested: HashMap<Item, u32>,
+}
+
+#[derive(Default)]
+struct RecipeBookLoader;
+
+#[derive(Debug, Error)]
+enum RecipeBookLoaderError {
+  #[error("Could not read recipes: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Could not parse recipes: {0}")]
+  Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for RecipeBookLoader {
+  type Asset = RecipeBook;
+  type Settings = ();
+  type Error = RecipeBookLoaderError;
+
+  async fn load<'a>(
+    &'a self,
+    reader: &'a mut Reader<'_>,
+    _settings: &'a (),
+    _load_context: &'a mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    Ok(ron::de::from_bytes(&bytes)?)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["recipes.ron"]
+  }
+}
+
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
+  commands.insert_resource(Crafting {
+    book: server.load("recipes.ron"),
+    unlocked: HashSet::new(),
+    open: false,
+  });
+}
+
+fn track_harvests(
+  mut progress: ResMut<Progress>,
+  mut ev_harvest: EventReader<HarvestEvent>,
+) {
+  for ev in ev_harvest.read() {
+    *progress.harvested.entry(ev.item.clone()).or_insert(0) += ev.amount;
+  }
+}
+
+fn unlock_recipes(
+  books: Res<Assets<RecipeBook>>,
+  progress: Res<Progress>,
+  wallet: Res<Wallet>,
+  mut crafting: ResMut<Crafting>,
+  mut ev_notice: EventWriter<Notice>,
+) {
+  let Some(book) = books.get(&crafting.book) else {
+    return;
+  };
+  let unlocked: Vec<&Recipe> = book
+    .recipes
+    .iter()
+    .filter(|recipe| !crafting.unlocked.contains(&recipe.name))
+    .filter(|recipe| recipe.unlock.is_met(&progress, &wallet))
+    .collect();
+  for recipe in unlocked {
+    // Recipes known from the start don't need announcing
+    if !matches!(recipe.unlock, Unlock::Start) {
+      ev_notice.send(Notice(format!("New recipe: {}", recipe.name)));
+    }
+    crafting.unlocked.insert(recipe.name.clone());
+  }
+}
+
+fn toggle_menu(kbd: Res<ButtonInput<KeyCode>>, mut crafting: ResMut<Crafting>) {
+  if kbd.just_pressed(KeyCode::KeyC) {
+    crafting.open = !crafting.open;
+  } else if kbd.just_pressed(KeyCode::Escape) {
+    crafting.open = false;
+  }
+}
+
+fn crafting_window(
+  mut contexts: EguiContexts,
+  mut crafting: ResMut<Crafting>,
+  books: Res<Assets<RecipeBook>>,
+  items: ItemData,
+  mut tiers: ResMut<ToolTiers>,
+  trader: Trader,
+) {
+  if !crafting.open {
+    return;
+  }
+  let Trader {
+    mut wallet,
+    mut q_inventory,
+    mut ev_notice,
+  } = trader;
+  let (Some(book), Ok(mut inventory)) =
+    (books.get(&crafting.book), q_inventory.get_single_mut())
+  else {
+    return;
+  };
+
+  let mut open = true;
+  egui::Window::new("Crafting")
+    .open(&mut open)
+    .collapsible(false)
+    .resizable(false)
+    .show(contexts.ctx_mut(), |ui| {
+      egui::Grid::new("recipes").striped(true).show(ui, |ui| {
+        for recipe in book
+          .recipes
+          .iter()
+          .filter(|recipe| crafting.unlocked.contains(&recipe.name))
+        {
+          ui.label(&recipe.name);
+          let mut inputs: Vec<String> = recipe
+            .inputs
+            .iter()
+            .map(|input| {
+              let name = items
+                .info(&input.item)
+                .map_or(input.item.0.as_str(), |info| info.name.as_str());
+              format!(
+                "{} {} ({})",
+                input.amount,
+                name,
+                inventory.count(&input.item)
+              )
+            })
+            .collect();
+          if recipe.cost > 0 {
+            inputs.push(format!("{}g", recipe.cost));
+          }
+          ui.label(inputs.join(", "));
+          let craftable = recipe.can_craft(&inventory, &wallet, &tiers);
+          if ui
+            .add_enabled(craftable, egui::Button::new("Craft"))
+            .clicked()
+          {
+            if let Some(message) =
+              recipe.craft(&items, &mut inventory, &mut wallet, &mut tiers)
+            {
+              ev_notice.send(Notice(message));
+            }
+          }
+          ui.end_row();
+        }
+      });
+    });
+  crafting.open &= open;
+}
+
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<RecipeBook>()
+      .init_asset_loader::<RecipeBookLoader>()
+      .init_resource::<Progress>()
+      .add_systems(Startup, setup)
+      .add_systems(
+        Update,
+        (track_harvests, unlock_recipes, toggle_menu, crafting_window),
+      );
+  }
+}
+
+impl Unlock {
+  pub fn is_met(&self, progress: &Progress, wallet: &Wallet) -> bool {
+    match self {
+      Unlock::Start => true,
+      Unlock::Harvested(item, amount) => {
+        progress.harvested.get(item).copied().unwrap_or(0) >= *amount
+      }
+      Unlock::Earned(amount) => wallet.earned >= *amount,
+    }
+  }
+}
+
+impl Recipe {
+  pub fn can_craft(
+    &self,
+    inventory: &Inventory,
+    wallet: &Wallet,
+    tiers: &ToolTiers,
+  ) -> bool {
+    let upgradable = match &self.output {
+      RecipeOutput::Item(_) => true,
+      RecipeOutput::Upgrade(tool, tier) => {
+        tiers.get(tool).next() == Some(*tier)
+      }
+    };
+    upgradable
+      && wallet.money >= self.cost
+      && self
+        .inputs
+        .iter()
+        .all(|input| inventory.count(&input.item) >= input.amount)
+  }
+
+  // Consumes the inputs and hands out the output, returning why it failed
+  pub fn craft(
+    &self,
+    items: &ItemData,
+    inventory: &mut Inventory,
+    wallet: &mut Wallet,
+    tiers: &mut ToolTiers,
+  ) -> Option<String> {
+    if let RecipeOutput::Item(output) = &self.output {
+      let max_stack = items.max_stack(&output.item);
+      if !inventory.fits(&output.item, output.amount, max_stack) {
+        return Some("Inventory full".to_owned());
+      }
+    }
+    if !wallet.spend(self.cost) {
+      return Some("Not enough money".to_owned());
+    }
+    for input in &self.inputs {
+      inventory.take(&input.item, input.amount);
+    }
+    match &self.output {
+      RecipeOutput::Item(output) => {
+        let max_stack = items.max_stack(&output.item);
+        inventory.add(output.item.clone(), output.amount, max_stack);
+      }
+      RecipeOutput::Upgrade(tool, _) => {
+        tiers.upgrade(tool);
+      }
+    }
+    None
+  }
+}
@@ -0,0 +1,302 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+  app::{App, Plugin, Startup},
+  asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets,
+    AsyncReadExt, Handle, LoadContext,
+  },
+  ecs::system::SystemParam,
+  prelude::{Commands, Res, Resource},
+  reflect::TypePath,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+  daycycle::Season,
+  inventory::Item,
+  soil::{Nutrients, SoilModel},
+};
+
+#[derive(Deserialize)]
+pub struct CropStage {
+  pub days: u32,
+  pub texture: u32,
+  pub watered_texture: u32,
+  pub wilted_texture: u32,
+  pub watered_wilted_texture: u32,
+  pub dead_texture: u32,
+  pub watered_dead_texture: u32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct CropYield {
+  pub item: Item,
+  pub amount: u32,
+}
+
+#[derive(Deserialize)]
+pub struct CropSpecies {
+  pub name: String,
+  pub seed: Item,
+  pub seasons: Vec<Season>,
+  pub stages: Vec<CropStage>,
+  pub harvest: CropYield,
+  // Seeds dropped alongside the harvest
+  pub seed_yield: u32,
+  // Nutrients taken from the soil by each harvest
+  pub nutrients: Nutrients,
+  // Stage the crop goes back to once harvested, None clears the farmland
+  pub regrow: Option<usize>,
+}
+
+// Maps arability linearly from `low` (at 0%) to `high` (at 100%), bent by
+// `exponent`
+#[derive(Deserialize)]
+pub struct ArabilityCurve {
+  pub low: f32,
+  pub high: f32,
+  pub exponent: f32,
+}
+
+#[derive(Deserialize)]
+pub struct ArabilityModel {
+  // Days worth of growth a watered crop gains overnight
+  pub growth: ArabilityCurve,
+  // Multiplier applied to the species' harvest amount
+  pub harvest: ArabilityCurve,
+  // Chance a watered crop fails overnight
+  pub failure: ArabilityCurve,
+}
+
+#[derive(Deserialize)]
+pub struct DroughtModel {
+  // Days without water before a crop wilts
+  pub wilt_after: u32,
+  // Days without water before a crop dies
+  pub die_after: u32,
+}
+
+#[derive(Deserialize)]
+pub struct NeglectModel {
+  // Days empty farmland stays cultivated before going back to grass
+  pub revert_after: u32,
+  // Daily chance of weeds showing up on empty farmland
+  pub weed_chance: f64,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct CropRegistry {
+  pub arability: ArabilityModel,
+  pub soil: SoilModel,
+  pub drought: DroughtModel,
+  pub neglect: NeglectModel,
+  species: BTreeMap<String, CropSpecies>,
+}
+
+#[derive(Clone)]
+pub struct Crop {
+  pub species: String,
+  pub stage: usize,
+  pub growth: f32,
+  pub dry_days: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CropCondition {
+  Healthy,
+  Wilted,
+  Dead,
+}
+
+#[derive(Resource)]
+pub struct Crops {
+  registry: Handle<CropRegistry>,
+}
+
+#[derive(SystemParam)]
+pub struct CropData<'w> {
+  crops: Res<'w, Crops>,
+  registries: Res<'w, Assets<CropRegistry>>,
+}
+
+#[derive(Default)]
+struct CropRegistryLoader;
+
+#[derive(Debug, Error)]
+enum CropRegistryLoaderError {
+  #[error("Could not read crop definitions: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Could not parse crop definitions: {0}")]
+  Ron(#[from] ron::error::SpannedError),
+  #[error("Crop {0} regrows to stage {1} but only has {2} stages")]
+  Regrow(String, usize, usize),
+  #[error("Weed chance {0} is not between 0 and 1")]
+  WeedChance(f64),
+}
+
+impl AssetLoader for CropRegistryLoader {
+  type Asset = CropRegistry;
+  type Settings = ();
+  type Error = CropRegistryLoaderError;
+
+  async fn load<'a>(
+    &'a self,
+    reader: &'a mut Reader<'_>,
+    _settings: &'a (),
+    _load_context: &'a mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    let registry: CropRegistry = ron::de::from_bytes(&bytes)?;
+    for (id, species) in &registry.species {
+      let stages = species.stages.len();
+      if let Some(stage) = species.regrow.filter(|&stage| stage >= stages) {
+        return Err(CropRegistryLoaderError::Regrow(id.clone(), stage, stages));
+      }
+    }
+    let weed_chance = registry.neglect.weed_chance;
+    if !(0.0..=1.0).contains(&weed_chance) {
+      return Err(CropRegistryLoaderError::WeedChance(weed_chance));
+    }
+    Ok(registry)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["crops.ron"]
+  }
+}
+
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
+  commands.insert_resource(Crops {
+    registry: server.load("crops.ron"),
+  });
+}
+
+pub struct CropsPlugin;
+
+impl Plugin for CropsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<CropRegistry>()
+      .init_asset_loader::<CropRegistryLoader>()
+      .add_systems(Startup, setup);
+  }
+}
+
+impl CropRegistry {
+  pub fn get(&self, id: &str) -> Option<&CropSpecies> {
+    self.species.get(id)
+  }
+
+  // Species grown from `seed`, with its id
+  pub fn planted_from(&self, seed: &Item) -> Option<(&str, &CropSpecies)> {
+    self
+      .species
+      .iter()
+      .find(|(_, species)| species.seed == *seed)
+      .map(|(id, species)| (id.as_str(), species))
+  }
+}
+
+impl CropYield {
+  pub fn scaled(&self, factor: f32) -> Self {
+    Self {
+      item: self.item.clone(),
+      amount: ((self.amount as f32 * factor).round() as u32).max(1),
+    }
+  }
+}
+
+impl CropStage {
+  pub fn texture(&self, condition: CropCondition, watered: bool) -> u32 {
+    match (condition, watered) {
+      (CropCondition::Healthy, false) => self.texture,
+      (CropCondition::Healthy, true) => self.watered_texture,
+      (CropCondition::Wilted, false) => self.wilted_texture,
+      (CropCondition::Wilted, true) => self.watered_wilted_texture,
+      (CropCondition::Dead, false) => self.dead_texture,
+      (CropCondition::Dead, true) => self.watered_dead_texture,
+    }
+  }
+}
+
+impl CropSpecies {
+  pub fn ripe_stage(&self) -> usize {
+    self.stages.len().saturating_sub(1)
+  }
+}
+
+impl Crop {
+  pub fn new(species: &str) -> Self {
+    Self {
+      species: species.to_owned(),
+      stage: 0,
+      growth: 0.,
+      dry_days: 0,
+    }
+  }
+
+  pub fn is_ripe(&self, species: &CropSpecies) -> bool {
+    self.stage >= species.ripe_stage()
+  }
+
+  pub fn condition(&self, drought: &DroughtModel) -> CropCondition {
+    if self.dry_days >= drought.die_after {
+      CropCondition::Dead
+    } else if self.dry_days >= drought.wilt_after {
+      CropCondition::Wilted
+    } else {
+      CropCondition::Healthy
+    }
+  }
+
+  pub fn grow(&mut self, species: &CropSpecies, rate: f32) {
+    if self.is_ripe(species) {
+      return;
+    }
+    self.growth += rate;
+    while !self.is_ripe(species)
+      && self.growth >= species.stages[self.stage].days as f32
+    {
+      self.growth -= species.stages[self.stage].days as f32;
+      self.stage += 1;
+    }
+  }
+}
+
+impl ArabilityCurve {
+  // Bad curves, like a negative exponent at 0%, give 0 instead of inf or NaN
+  pub fn at(&self, arability: f32) -> f32 {
+    let t = arability.clamp(0., 1.).powf(self.exponent);
+    let value = self.low + (self.high - self.low) * t;
+    if value.is_finite() {
+      value
+    } else {
+      0.
+    }
+  }
+}
+
+impl ArabilityModel {
+  pub fn failure_chance(&self, arability: f32) -> f64 {
+    self.failure.at(arability).clamp(0., 1.) as f64
+  }
+}
+
+impl CropData<'_> {
+  pub fn registry(&self) -> Option<&CropRegistry> {
+    self.registries.get(&self.crops.registry)
+  }
+
+  pub fn species(&self, id: &str) -> Option<&CropSpecies> {
+    self.registry().and_then(|registry| registry.get(id))
+  }
+
+  pub fn planted_from(&self, seed: &Item) -> Option<(&str, &CropSpecies)> {
+    self
+      .registry()
+      .and_then(|registry| registry.planted_from(seed))
+  }
+}
@@ -1,16 +1,50 @@
 use bevy::{
   app::{App, Plugin, Update},
   log::info,
-  prelude::{Event, EventWriter, Res, ResMut, Resource},
+  prelude::{
+    Event, EventWriter, IntoSystemConfigs, Res, ResMut, Resource, SystemSet,
+  },
   time::Time,
 };
+use serde::Deserialize;
 use std::time::Duration;
 
 const DAY_LEN_SEC: f32 = 240.;
+const DAYS_PER_SEASON: usize = 28;
+const SEASONS_PER_YEAR: usize = 4;
 
 #[derive(Event)]
 pub struct NewDayEvent;
 
+// The day advances in this set, new day readers run after it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct DayTick;
+
+// Sent along with the new day event on the first day of a season
+#[derive(Event)]
+pub struct SeasonChangedEvent {
+  pub season: Season,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Season {
+  Spring,
+  Summer,
+  Autumn,
+  Winter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Weekday {
+  Monday,
+  Tuesday,
+  Wednesday,
+  Thursday,
+  Friday,
+  Saturday,
+  Sunday,
+}
+
 #[derive(PartialEq)]
 pub enum TimeMode {
   Day,
@@ -33,9 +67,16 @@ fn tick(
   time: Res<Time>,
   mut day: ResMut<DayCycle>,
   mut ev_newday: EventWriter<NewDayEvent>,
+  mut ev_season: EventWriter<SeasonChangedEvent>,
 ) {
   if day.tick(time.delta()) {
     ev_newday.send(NewDayEvent);
+    if day.day_of_season() == 1 {
+      info!("{:?} of year {} begins", day.season(), day.year());
+      ev_season.send(SeasonChangedEvent {
+        season: day.season(),
+      });
+    }
   }
 }
 
@@ -65,10 +106,57 @@ impl DayCycle {
     }
     false
   }
+
+  // Days passed since the morning of day 1
+  pub fn elapsed(&self) -> f32 {
+    (self.day - 1) as f32 + self.daytime
+  }
+
   pub fn sleep(&mut self) {
     info!("Sleeping");
     self.mode = TimeMode::Night;
   }
+
+  // Day 1 is the first Monday of spring in year 1
+  pub fn season(&self) -> Season {
+    match (self.day - 1) / DAYS_PER_SEASON % SEASONS_PER_YEAR {
+      0 => Season::Spring,
+      1 => Season::Summer,
+      2 => Season::Autumn,
+      _ => Season::Winter,
+    }
+  }
+
+  // Day within the current season, starting at 1
+  pub fn day_of_season(&self) -> usize {
+    (self.day - 1) % DAYS_PER_SEASON + 1
+  }
+
+  pub fn year(&self) -> usize {
+    (self.day - 1) / (DAYS_PER_SEASON * SEASONS_PER_YEAR) + 1
+  }
+
+  // Date shown to the player, like "Spring 3, Year 1"
+  pub fn date(&self) -> String {
+    format!(
+      "{:?} {}, Year {}",
+      self.season(),
+      self.day_of_season(),
+      self.year()
+    )
+  }
+
+  pub fn weekday(&self) -> Weekday {
+    match (self.day - 1) % 7 {
+      0 => Weekday::Monday,
+      1 => Weekday::Tuesday,
+      2 => Weekday::Wednesday,
+      3 => Weekday::Thursday,
+      4 => Weekday::Friday,
+      5 => Weekday::Saturday,
+      _ => Weekday::Sunday,
+    }
+  }
 }
 
 pub struct DayCyclePlugin;
@@ -81,7 +169,8 @@ impl Plugin for DayCyclePlugin {
         mode: TimeMode::Day,
         day: 1,
       })
-      .add_systems(Update, tick)
-      .add_event::<NewDayEvent>();
+      .add_systems(Update, tick.in_set(DayTick))
+      .add_event::<NewDayEvent>()
+      .add_event::<SeasonChangedEvent>();
   }
 }
@@ -0,0 +1,238 @@
+use std::f32::consts::TAU;
+
+use avian2d::prelude::{
+  Collider, LinearDamping, LinearVelocity, Mass, RigidBody, Sensor,
+};
+use bevy::{
+  app::{App, Plugin, Update},
+  asset::AssetServer,
+  log::info,
+  math::Vec2,
+  prelude::{
+    default, Bundle, Commands, Component, Entity, Event, EventReader,
+    EventWriter, GlobalTransform, IntoSystemConfigs, Query, Res, Transform,
+    With, Without,
+  },
+  sprite::{Sprite, SpriteBundle},
+  time::{Time, Timer, TimerMode},
+};
+use bevy_ecs_ldtk::GridCoords;
+use rand::{thread_rng, Rng};
+
+use super::{
+  daycycle::{DayTick, NewDayEvent},
+  interface::Notice,
+  inventory::{Inventory, Item, ItemData, Stack},
+  player::Player,
+  tools::HarvestEvent,
+  world::tile_center,
+};
+use crate::components::physics::{Acceleration, PhysicsBundle};
+
+// Drops closer than this to the player get pulled in
+const MAGNET_RADIUS: f32 = 32.;
+// Drops closer than this to the player are picked up
+const PICKUP_RADIUS: f32 = 6.;
+// Time a fresh drop scatters before the player can pull it in
+const PICKUP_DELAY_SECS: f32 = 0.5;
+const SCATTER_SPEED: (f32, f32) = (20., 60.);
+
+// Asks for items to be dropped on the ground at a world position
+#[derive(Event)]
+pub struct DropEvent {
+  pub at: Vec2,
+  pub item: Item,
+  pub amount: u32,
+}
+
+#[derive(Component)]
+pub struct ItemDrop {
+  pub stack: Stack,
+  delay: Timer,
+  // Whether the player was told this drop doesn't fit, reset once they leave
+  warned: bool,
+}
+
+#[derive(Bundle)]
+struct ItemDropBundle {
+  drop: ItemDrop,
+  sprite: SpriteBundle,
+  physics: PhysicsBundle,
+  sensor: Sensor,
+}
+
+fn drop_harvest(
+  mut ev_harvest: EventReader<HarvestEvent>,
+  mut ev_drop: EventWriter<DropEvent>,
+  q_tiles: Query<&GridCoords>,
+) {
+  for ev in ev_harvest.read() {
+    let Ok(coords) = q_tiles.get(ev.tile) else {
+      continue;
+    };
+    ev_drop.send(DropEvent {
+      at: tile_center(*coords),
+      item: ev.item.clone(),
+      amount: ev.amount,
+    });
+  }
+}
+
+fn spawn_drops(
+  items: ItemData,
+  server: Res<AssetServer>,
+  mut ev_drop: EventReader<DropEvent>,
+  mut commands: Commands,
+) {
+  let mut rng = thread_rng();
+  for ev in ev_drop.read() {
+    let Some(info) = items.info(&ev.item) else {
+      continue;
+    };
+    let speed = rng.gen_range(SCATTER_SPEED.0..SCATTER_SPEED.1);
+    let velocity = Vec2::from_angle(rng.gen_range(0. ..TAU)) * speed;
+    commands.spawn(ItemDropBundle {
+      drop: ItemDrop {
+        stack: Stack {
+          item: ev.item.clone(),
+          amount: ev.amount,
+        },
+        delay: Timer::from_seconds(PICKUP_DELAY_SECS, TimerMode::Once),
+        warned: false,
+      },
+      sprite: SpriteBundle {
+        texture: server.load(&info.icon),
+        sprite: Sprite {
+          custom_size: Some(Vec2::splat(10.)),
+          ..default()
+        },
+        transform: Transform::from_translation(ev.at.extend(6.)),
+        ..default()
+      },
+      physics: PhysicsBundle {
+        body: RigidBody::Dynamic,
+        collider: Collider::circle(3.),
+        acceleration: Acceleration(512.),
+        linear_velocity: LinearVelocity(velocity),
+        linear_damping: LinearDamping(6.),
+        mass: Mass(1.),
+        ..default()
+      },
+      sensor: Sensor,
+    });
+  }
+}
+
+fn attract_drops(
+  time: Res<Time>,
+  items: ItemData,
+  q_player: Query<(&GlobalTransform, &Inventory), With<Player>>,
+  mut q_drops: Query<
+    (
+      &Transform,
+      &mut LinearVelocity,
+      &Acceleration,
+      &mut ItemDrop,
+    ),
+    Without<Player>,
+  >,
+) {
+  let Ok((player, inventory)) = q_player.get_single() else {
+    return;
+  };
+  let player = player.translation().truncate();
+  for (transform, mut velocity, acceleration, mut drop) in &mut q_drops {
+    if !drop.delay.tick(time.delta()).finished() {
+      continue;
+    }
+    // Only pull in what the inventory has room for
+    let max_stack = items.max_stack(&drop.stack.item);
+    if !inventory.fits(&drop.stack.item, 1, max_stack) {
+      continue;
+    }
+    let offset = player - transform.translation.truncate();
+    if offset.length() < MAGNET_RADIUS {
+      velocity.0 +=
+        offset.normalize_or_zero() * acceleration.0 * time.delta_seconds();
+    }
+  }
+}
+
+fn collect_drops(
+  items: ItemData,
+  mut q_player: Query<(&GlobalTransform, &mut Inventory), With<Player>>,
+  mut q_drops: Query<(Entity, &Transform, &mut ItemDrop), Without<Player>>,
+  mut ev_notice: EventWriter<Notice>,
+  mut commands: Commands,
+) {
+  let Ok((player, mut inventory)) = q_player.get_single_mut() else {
+    return;
+  };
+  let player = player.translation().truncate();
+  let mut full = false;
+  for (entity, transform, mut drop) in &mut q_drops {
+    if !drop.delay.finished() {
+      continue;
+    }
+    if transform.translation.truncate().distance(player) > PICKUP_RADIUS {
+      if drop.warned {
+        drop.warned = false;
+      }
+      continue;
+    }
+    let max_stack = items.max_stack(&drop.stack.item);
+    let left =
+      inventory.add(drop.stack.item.clone(), drop.stack.amount, max_stack);
+    if left == drop.stack.amount {
+      full |= !drop.warned;
+      drop.warned = true;
+      continue;
+    }
+    info!(
+      "Picked up {} {}",
+      drop.stack.amount - left,
+      drop.stack.item.0
+    );
+    if left == 0 {
+      commands.entity(entity).despawn();
+    } else {
+      drop.stack.amount = left;
+    }
+  }
+  if full {
+    ev_notice.send(Notice("Inventory full".to_owned()));
+  }
+}
+
+// Whatever is left on the ground rots overnight
+fn clear_drops(
+  mut ev_newday: EventReader<NewDayEvent>,
+  q_drops: Query<Entity, With<ItemDrop>>,
+  mut commands: Commands,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  for entity in &q_drops {
+    commands.entity(entity).despawn();
+  }
+}
+
+pub struct DropsPlugin;
+
+impl Plugin for DropsPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_event::<DropEvent>().add_systems(
+      Update,
+      (
+        drop_harvest,
+        spawn_drops,
+        attract_drops,
+        collect_drops,
+        clear_drops.after(DayTick),
+      ),
+    );
+  }
+}
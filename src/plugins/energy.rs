@@ -0,0 +1,54 @@
+use bevy::{
+  app::{App, Plugin, Update},
+  prelude::{EventReader, IntoSystemConfigs, ResMut, Resource},
+};
+
+use super::daycycle::{DayTick, NewDayEvent};
+
+const MAX_ENERGY: u32 = 100;
+
+#[derive(Resource)]
+pub struct Energy {
+  pub current: u32,
+  pub max: u32,
+}
+
+// A new day only starts after sleeping, so that is when energy comes back
+fn restore(
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut energy: ResMut<Energy>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  energy.current = energy.max;
+}
+
+pub struct EnergyPlugin;
+
+impl Plugin for EnergyPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .insert_resource(Energy {
+        current: MAX_ENERGY,
+        max: MAX_ENERGY,
+      })
+      .add_systems(Update, restore.after(DayTick));
+  }
+}
+
+impl Energy {
+  pub fn can_afford(&self, cost: u32) -> bool {
+    self.current >= cost && self.current > 0
+  }
+
+  pub fn spend(&mut self, cost: u32) {
+    self.current = self.current.saturating_sub(cost);
+  }
+
+  pub fn fraction(&self) -> f32 {
+    self.current as f32 / self.max as f32
+  }
+}
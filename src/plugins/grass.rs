@@ -1,19 +1,38 @@
+use std::collections::HashSet;
+
 use super::{
+  controls::Direction,
+  crops::{Crop, CropCondition, CropData, CropRegistry, CropYield},
+  daycycle::{DayTick, NewDayEvent, SeasonChangedEvent},
   gen::WorldGen,
   interface::Interface,
-  tools::Tool,
-  world::{TileType, WorldIndex},
+  inventory::Inventory,
+  player::{in_reach, Player, ToolUse},
+  soil::{Fertilized, Soil},
+  tools::ToolContext,
+  world::{tile_center, TileType, WorldIndex},
 };
 use bevy::{
   app::{App, Plugin, Update},
+  asset::AssetEvent,
+  ecs::{query::QueryData, system::SystemParam},
   input::ButtonInput,
+  log::info,
   prelude::{
-    Bundle, Commands, Component, Entity, MouseButton, ParallelCommands, Query,
-    Res, With, Without,
+    Bundle, Changed, Commands, Component, Entity, EventReader, GlobalTransform,
+    Has, IntoSystemConfigs, IntoSystemSetConfigs, MouseButton, Or, Query,
+    RemovedComponents, Res, SystemSet, With, Without,
   },
+  time::Time,
+};
+use bevy_ecs_ldtk::{
+  app::LdtkIntCellAppExt, GridCoords, LdtkIntCell, LevelEvent,
 };
-use bevy_ecs_ldtk::{app::LdtkIntCellAppExt, GridCoords, LdtkIntCell};
 use bevy_ecs_tilemap::tiles::TileTextureIndex;
+use rand::{thread_rng, Rng};
+
+// Plain grass, the only texture farmland can be cultivated on
+const GRASS_TEXTURE: u32 = 31;
 
 #[derive(Default, Component)]
 pub struct Grass;
@@ -21,19 +40,50 @@ pub struct Grass;
 #[derive(Component)]
 pub struct Arability(pub f32);
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum FarmStage {
-  Empty,
-  Sprout,
-  Vegetative,
-  Ripening,
+#[derive(Component)]
+pub struct Cultivable;
+
+#[derive(Default, Component)]
+pub struct Farmland {
+  pub crop: Option<Crop>,
+  pub weeds: bool,
+  // Days spent without a crop
+  pub idle_days: u32,
 }
 
 #[derive(Component)]
-pub struct Farmland(pub FarmStage);
+pub struct Watered;
+
+pub enum Harvest {
+  // Weeds or a dead crop were removed
+  Cleared,
+  Crop(Vec<CropYield>),
+}
 
+// Marks a tile that has an object placed on it
 #[derive(Component)]
-pub struct Watered;
+pub struct Occupied;
+
+// Level entity standing on a tile, which can't be farmed under it
+#[derive(Default, Component)]
+pub struct OccupiesGround;
+
+// Daily growth, which also dries out yesterday's watered tiles
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct Growth;
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct FarmTile {
+  pub farmland: Option<&'static mut Farmland>,
+  pub arability: &'static Arability,
+  pub soil: &'static mut Soil,
+  pub coords: &'static GridCoords,
+  pub cultivable: Has<Cultivable>,
+  pub watered: Has<Watered>,
+  pub fertilized: Has<Fertilized>,
+  pub occupied: Has<Occupied>,
+}
 
 #[derive(Default, Bundle, LdtkIntCell)]
 struct GrassBundle {
@@ -43,79 +93,272 @@ struct GrassBundle {
 fn gen(
   world_gen: Res<WorldGen>,
   mut commands: Commands,
-  grass_uninit: Query<(Entity, &GridCoords), (With<Grass>, Without<Arability>)>,
+  grass_uninit: Query<
+    (Entity, &GridCoords, &TileTextureIndex),
+    (With<Grass>, Without<Arability>),
+  >,
 ) {
-  for (entity, coords) in &grass_uninit {
+  for (entity, coords, index) in &grass_uninit {
     let (x, y) = (coords.x, coords.y);
     let v = world_gen.at(x, y);
-    commands.entity(entity).insert(Arability(v));
+    let mut tile = commands.entity(entity);
+    tile.insert(Arability(v));
+    if index.0 == GRASS_TEXTURE {
+      tile.insert(Cultivable);
+    }
   }
 }
 
-fn apply_texture(
-  mut q_grass: Query<
-    (
-      Entity,
-      &mut TileTextureIndex,
-      Option<&Farmland>,
-      Option<&Watered>,
-    ),
-    With<Arability>,
-  >,
-  par_commands: ParallelCommands,
+type FreeGrass = (With<Grass>, Without<Occupied>);
+
+fn occupy_ground(
+  mut ev_levels: EventReader<LevelEvent>,
+  q_objects: Query<&GridCoords, With<OccupiesGround>>,
+  q_grass: Query<(Entity, &GridCoords), FreeGrass>,
+  mut commands: Commands,
 ) {
-  q_grass
-    .par_iter_mut()
-    .for_each(|(entity, mut index, farmland, watered)| {
-      index.0 = if let Some(Farmland(stage)) = farmland {
-        if index.0 != 31 && index.0 < 50 {
-          par_commands.command_scope(|mut commands| {
-            commands
-              .entity(entity)
-              .remove::<Farmland>()
-              .remove::<Watered>();
-          });
-          index.0
-        } else {
-          let base = stage.atlas_index();
-          if watered.is_some() {
-            (base + 5) as u32
-          } else {
-            base as u32
-          }
-        }
-      } else {
-        if index.0 >= 50 {
-          31
-        } else {
-          index.0
+  for ev in ev_levels.read() {
+    if let LevelEvent::Spawned(_) = ev {
+      let occupied: HashSet<GridCoords> = q_objects.iter().copied().collect();
+      for (entity, coords) in &q_grass {
+        if occupied.contains(coords) {
+          commands.entity(entity).insert(Occupied);
         }
-      };
+      }
+    }
+  }
+}
+
+type FarmStateChanged =
+  (With<Farmland>, Or<(Changed<Farmland>, Changed<Watered>)>);
+type FarmTexture = (
+  Option<&'static Farmland>,
+  Has<Watered>,
+  &'static mut TileTextureIndex,
+);
+
+fn render_farmland(
+  crops: CropData,
+  mut ev_registry: EventReader<AssetEvent<CropRegistry>>,
+  mut removed_farmland: RemovedComponents<Farmland>,
+  mut removed_watered: RemovedComponents<Watered>,
+  q_changed: Query<Entity, FarmStateChanged>,
+  q_farmland: Query<Entity, With<Farmland>>,
+  mut q_tiles: Query<FarmTexture, With<Grass>>,
+) {
+  // Crop textures come from the registry, so redraw everything once it loads
+  let mut dirty: HashSet<Entity> = if ev_registry.read().count() > 0 {
+    q_farmland.iter().collect()
+  } else {
+    q_changed.iter().collect()
+  };
+  dirty.extend(removed_farmland.read());
+  dirty.extend(removed_watered.read());
+
+  for entity in dirty {
+    let Ok((farmland, watered, mut index)) = q_tiles.get_mut(entity) else {
+      continue;
+    };
+    index.0 = farmland.map_or(GRASS_TEXTURE, |farmland| {
+      farmland.atlas_index(&crops, watered)
     });
+  }
+}
+
+fn grow(
+  crops: CropData,
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut q_farmland: Query<(
+    Entity,
+    &mut Farmland,
+    &Arability,
+    &GridCoords,
+    Has<Watered>,
+  )>,
+  mut commands: Commands,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  let mut rng = thread_rng();
+  for (entity, mut farmland, arability, coords, watered) in &mut q_farmland {
+    if watered {
+      commands.entity(entity).remove::<Watered>();
+    }
+
+    let Some(registry) = crops.registry() else {
+      continue;
+    };
+    let Some(crop) = &mut farmland.crop else {
+      continue;
+    };
+    let Some(species) = registry.get(&crop.species) else {
+      continue;
+    };
+    if crop.is_ripe(species)
+      || crop.condition(&registry.drought) == CropCondition::Dead
+    {
+      continue;
+    }
+    if !watered {
+      crop.dry_days += 1;
+      if crop.condition(&registry.drought) == CropCondition::Dead {
+        info!("{} died of thirst at {coords:?}", species.name);
+      }
+      continue;
+    }
+    crop.dry_days = 0;
+
+    let model = &registry.arability;
+    if rng.gen_bool(model.failure_chance(arability.0)) {
+      info!("{} failed at {coords:?}", species.name);
+      farmland.crop = None;
+    } else {
+      crop.grow(species, model.growth.at(arability.0));
+    }
+  }
 }
 
+fn neglect(
+  crops: CropData,
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut q_farmland: Query<(Entity, &mut Farmland)>,
+  mut commands: Commands,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  let Some(registry) = crops.registry() else {
+    return;
+  };
+  let model = &registry.neglect;
+  let mut rng = thread_rng();
+  for (entity, mut farmland) in &mut q_farmland {
+    if farmland.crop.is_some() {
+      farmland.idle_days = 0;
+      continue;
+    }
+
+    farmland.idle_days += 1;
+    if farmland.idle_days >= model.revert_after {
+      commands.entity(entity).remove::<(Farmland, Watered)>();
+    } else if !farmland.weeds && rng.gen_bool(model.weed_chance) {
+      farmland.weeds = true;
+    }
+  }
+}
+
+// Crops that don't grow in the new season wither away
+fn end_season(
+  crops: CropData,
+  mut ev_season: EventReader<SeasonChangedEvent>,
+  mut q_farmland: Query<(&mut Farmland, &GridCoords)>,
+) {
+  let Some(season) = ev_season.read().last().map(|ev| ev.season) else {
+    return;
+  };
+  for (mut farmland, coords) in &mut q_farmland {
+    let Some(species) = farmland
+      .crop
+      .as_ref()
+      .and_then(|crop| crops.species(&crop.species))
+    else {
+      continue;
+    };
+    if !species.seasons.contains(&season) {
+      info!(
+        "{} withered at {coords:?} as {season:?} began",
+        species.name
+      );
+      farmland.crop = None;
+    }
+  }
+}
+
+#[derive(SystemParam)]
+struct ToolInput<'w> {
+  time: Res<'w, Time>,
+  interface: Res<'w, Interface>,
+  mouse: Res<'w, ButtonInput<MouseButton>>,
+}
+
+type ToolUser = (
+  Entity,
+  &'static mut Direction,
+  &'static GlobalTransform,
+  &'static mut Inventory,
+  Option<&'static mut ToolUse>,
+);
+
 fn use_tool(
-  tool: Res<Tool>,
-  interface: Res<Interface>,
-  mouse: Res<ButtonInput<MouseButton>>,
+  mut ctx: ToolContext,
+  input: ToolInput,
   world_index: Res<WorldIndex>,
-  mut q_grass: Query<Option<&mut Farmland>, With<Grass>>,
+  mut q_player: Query<ToolUser, With<Player>>,
+  mut q_grass: Query<FarmTile, With<Grass>>,
   mut commands: Commands,
 ) {
-  if mouse.pressed(MouseButton::Left) {
-    if let Some((commands, farmland)) = interface
-      .selected_grass(&world_index)
-      .map(|selected_grass| {
-        (
-          commands.entity(selected_grass),
-          q_grass.get_mut(selected_grass).ok(),
-        )
-      })
+  let Ok((player_entity, mut facing, player, mut inventory, tool_use)) =
+    q_player.get_single_mut()
+  else {
+    return;
+  };
+
+  // Start a swing with the held tool, turning toward the target first
+  let Some(mut tool_use) = tool_use else {
+    let held = inventory.selected().and_then(|stack| {
+      ctx
+        .items
+        .tool(&stack.item)
+        .map(|tool| (tool, stack.item.clone()))
+    });
+    if let Some((tool, item)) =
+      held.filter(|_| input.mouse.pressed(MouseButton::Left))
     {
-      let Some(farmland) = farmland else {
-        return;
-      };
-      tool.activate(commands, farmland);
+      let target = input.interface.cursor_grid_coords();
+      let delta = tile_center(target) - player.translation().truncate();
+      if let Some(direction) = Direction::towards(delta) {
+        *facing = direction;
+      }
+      commands
+        .entity(player_entity)
+        .insert(ToolUse::new(tool, item, target));
+    }
+    return;
+  };
+
+  tool_use.tick(input.time.delta());
+  if tool_use.finished() {
+    commands.entity(player_entity).remove::<ToolUse>();
+  }
+  if !tool_use.strike() {
+    return;
+  }
+
+  let tool = tool_use.tool;
+  for coords in ctx.tiers.area(&tool, tool_use.target, &facing) {
+    if !in_reach(player, coords) {
+      continue;
+    }
+    let Some(entity) = world_index
+      .get(coords)
+      .filter(|(_, typ)| *typ == TileType::Grass)
+      .map(|(entity, _)| entity)
+    else {
+      continue;
+    };
+    if let Ok(tile) = q_grass.get_mut(entity) {
+      tool.activate(
+        &tool_use.item,
+        &mut inventory,
+        commands.entity(entity),
+        tile,
+        &mut ctx,
+      );
     }
   }
 }
@@ -129,31 +372,96 @@ impl Plugin for GrassPlugin {
         "worldmap",
         TileType::Grass.index(),
       )
-      .add_systems(Update, (gen, apply_texture, use_tool));
+      .configure_sets(Update, Growth.after(DayTick))
+      .add_systems(
+        Update,
+        (
+          gen,
+          occupy_ground,
+          render_farmland,
+          grow.in_set(Growth),
+          end_season.after(Growth),
+          neglect.after(DayTick),
+          use_tool,
+        ),
+      );
   }
 }
 
-impl FarmStage {
-  pub fn plant(&mut self) {
-    if *self == FarmStage::Empty {
-      *self = FarmStage::Sprout;
+impl Farmland {
+  pub fn can_plant(&self) -> bool {
+    self.crop.is_none() && !self.weeds
+  }
+
+  pub fn plant(&mut self, species: &str) -> bool {
+    if self.can_plant() {
+      self.crop = Some(Crop::new(species));
+      true
+    } else {
+      false
     }
   }
-  pub fn next(&mut self) {
-    *self = match self {
-      FarmStage::Empty => FarmStage::Empty,
-      FarmStage::Sprout => FarmStage::Vegetative,
-      FarmStage::Vegetative => FarmStage::Ripening,
-      FarmStage::Ripening => FarmStage::Ripening,
+
+  pub fn atlas_index(&self, crops: &CropData, watered: bool) -> u32 {
+    let texture = self.crop.as_ref().and_then(|crop| {
+      let registry = crops.registry()?;
+      let stage = registry.get(&crop.species)?.stages.get(crop.stage)?;
+      Some(stage.texture(crop.condition(&registry.drought), watered))
+    });
+    match (texture, watered) {
+      (Some(texture), _) => texture,
+      (None, false) if self.weeds => 81,
+      (None, true) if self.weeds => 86,
+      (None, false) => 80,
+      (None, true) => 85,
     }
   }
+}
+
+impl FarmTileItem<'_> {
+  pub fn harvest(&mut self, crops: &CropData) -> Option<Harvest> {
+    let registry = crops.registry()?;
+    // Only borrow mutably once something is taken, so checking a tile every
+    // frame doesn't mark its farmland as changed
+    let farmland = self.farmland.as_deref()?;
+    if farmland.weeds {
+      self.farmland.as_mut()?.weeds = false;
+      return Some(Harvest::Cleared);
+    }
+    let crop = farmland.crop.as_ref()?;
+    if crop.condition(&registry.drought) == CropCondition::Dead {
+      self.farmland.as_mut()?.crop = None;
+      return Some(Harvest::Cleared);
+    }
+    let species = registry.get(&crop.species)?;
+    if !crop.is_ripe(species) {
+      return None;
+    }
+
+    let id = crop.species.clone();
+    let farmland = self.farmland.as_mut()?;
+    match species.regrow {
+      Some(stage) => {
+        let crop = farmland.crop.as_mut()?;
+        crop.stage = stage;
+        crop.growth = 0.;
+      }
+      None => farmland.crop = None,
+    }
 
-  pub fn atlas_index(&self) -> usize {
-    match self {
-      FarmStage::Empty => 80,
-      FarmStage::Sprout => 120,
-      FarmStage::Vegetative => 160,
-      FarmStage::Ripening => 160,
+    let factor = registry.arability.harvest.at(self.arability.0)
+      * registry
+        .soil
+        .harvest_factor(&self.soil, &id, &species.nutrients);
+    self.soil.nutrients.deplete(&species.nutrients);
+    self.soil.last_crop = Some(id);
+    let mut yields = vec![species.harvest.scaled(factor)];
+    if species.seed_yield > 0 {
+      yields.push(CropYield {
+        item: species.seed.clone(),
+        amount: species.seed_yield,
+      });
     }
+    Some(Harvest::Crop(yields))
   }
 }
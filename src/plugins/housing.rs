@@ -1,6 +1,7 @@
 use super::{
   daycycle::DayCycle,
   interface::Interface,
+  player::{in_reach, Player},
   world::{TileType, WorldIndex},
 };
 use avian2d::prelude::{Collider, RigidBody};
@@ -9,7 +10,9 @@ use bevy::{
   color::Color,
   input::ButtonInput,
   log::info,
-  prelude::{Bundle, Component, MouseButton, Res, ResMut},
+  prelude::{
+    Bundle, Component, GlobalTransform, MouseButton, Query, Res, ResMut, With,
+  },
   utils::default,
 };
 use bevy_ecs_ldtk::{app::LdtkIntCellAppExt, LdtkIntCell};
@@ -19,9 +22,15 @@ fn try_sleep(
   interface: Res<Interface>,
   mouse: Res<ButtonInput<MouseButton>>,
   world_index: Res<WorldIndex>,
+  q_player: Query<&GlobalTransform, With<Player>>,
   mut day: ResMut<DayCycle>,
 ) {
-  if mouse.just_pressed(MouseButton::Left) {
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  if mouse.just_pressed(MouseButton::Left)
+    && in_reach(player, interface.cursor_grid_coords())
+  {
     let tile = interface.selected_tile(&world_index);
     info!("fefefefe");
     if let Some(typ) = tile {
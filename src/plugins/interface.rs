@@ -1,42 +1,106 @@
 use super::{
   camera::MainCamera,
+  controls::Direction,
+  daycycle::DayCycle,
+  energy::Energy,
   grass::{Arability, Grass},
-  tools::Tool,
-  world::{TileType, WorldIndex},
+  inventory::{Inventory, ItemData, HOTBAR_SLOTS},
+  player::{in_reach, Player},
+  tools::{Tool, ToolTier, ToolTiers, WateringCan},
+  wallet::Wallet,
+  weather::Forecast,
+  world::{tile_center, TileType, WorldIndex},
 };
 use bevy::{
-  app::{App, Plugin, Startup, Update},
+  app::{App, Plugin, PreUpdate, Startup, Update},
   asset::AssetServer,
   color::{
-    palettes::css::{BLACK, RED},
-    Alpha,
+    palettes::css::{BLACK, GOLD, RED},
+    Alpha, Color,
   },
-  input::ButtonInput,
+  ecs::system::SystemParam,
+  input::{mouse::MouseWheel, ButtonInput, InputSystem},
   math::Vec2,
   prelude::{
-    default, BuildChildren, Camera, Commands, Component, Entity,
-    GlobalTransform, KeyCode, NodeBundle, Query, Res, ResMut, Resource,
-    TextBundle, Transform, Visibility, With,
+    default, BuildChildren, Camera, Changed, Commands, Component,
+    DetectChanges, Entity, Event, EventReader, GlobalTransform,
+    IntoSystemConfigs, KeyCode, MouseButton, NodeBundle, Query, Res, ResMut,
+    Resource, TextBundle, Transform, Visibility, With,
   },
   sprite::{Sprite, SpriteBundle},
   text::{Text, TextSection, TextStyle},
-  time::Time,
-  ui::{Display, FlexDirection, JustifyContent, Style, UiImage, UiRect, Val},
+  time::{Time, Timer, TimerMode},
+  ui::{
+    AlignItems, BackgroundColor, BorderColor, Display, FlexDirection,
+    JustifyContent, PositionType, Style, UiImage, UiRect, Val,
+  },
   window::{PrimaryWindow, Window},
 };
 use bevy_ecs_ldtk::GridCoords;
+use bevy_egui::EguiContexts;
 use core::f32;
 
+const NOTICE_SECS: f32 = 2.;
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+  KeyCode::Digit1,
+  KeyCode::Digit2,
+  KeyCode::Digit3,
+  KeyCode::Digit4,
+  KeyCode::Digit5,
+  KeyCode::Digit6,
+  KeyCode::Digit7,
+  KeyCode::Digit8,
+  KeyCode::Digit9,
+  KeyCode::Digit0,
+];
+const OUT_OF_REACH_TINT: Color = Color::srgb(1., 0.3, 0.3);
+
 #[derive(Resource)]
 pub struct Interface {
   pub cursor: Vec2,
 }
 
+// Mouse buttons together with the tile under the cursor
+#[derive(SystemParam)]
+pub struct Pointer<'w> {
+  pub interface: Res<'w, Interface>,
+  pub mouse: Res<'w, ButtonInput<MouseButton>>,
+}
+
 #[derive(Component)]
 struct ArabilityText;
 
 #[derive(Component)]
-struct ToolIcon;
+struct HotbarSlot(usize);
+
+#[derive(Component)]
+struct HotbarIcon(usize);
+
+#[derive(Component)]
+struct HotbarCount(usize);
+
+#[derive(Component)]
+struct EnergyFill;
+
+#[derive(Component)]
+struct WalletText;
+
+#[derive(Component)]
+struct DateText;
+
+#[derive(Component)]
+struct ForecastText;
+
+// Short message shown to the player, like why an action failed
+#[derive(Event)]
+pub struct Notice(pub String);
+
+#[derive(Component)]
+struct NoticeText(Timer);
+
+// Names the held item, along with its tool state like the tier
+#[derive(Component)]
+struct VariantText;
 
 #[derive(Component)]
 struct Selector;
@@ -76,27 +140,172 @@ fn setup(mut commands: Commands, server: Res<AssetServer>) {
         ArabilityText,
       ));
 
-      root.spawn((
-        NodeBundle {
+      root
+        .spawn(NodeBundle {
           style: Style {
-            width: Val::Px(48.),
-            height: Val::Px(48.),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(16.),
             ..default()
           },
           ..default()
-        },
-        UiImage::new(server.load("ui/cultivate.png")),
-        ToolIcon,
-      ));
+        })
+        .with_children(|toolbar| {
+          toolbar
+            .spawn(NodeBundle {
+              style: Style {
+                width: Val::Px(192.),
+                height: Val::Px(24.),
+                padding: UiRect::all(Val::Px(4.)),
+                ..default()
+              },
+              background_color: BackgroundColor(BLACK.into()),
+              ..default()
+            })
+            .with_children(|bar| {
+              bar.spawn((
+                NodeBundle {
+                  style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    ..default()
+                  },
+                  background_color: BackgroundColor(GOLD.into()),
+                  ..default()
+                },
+                EnergyFill,
+              ));
+            });
+          toolbar.spawn((
+            TextBundle::from_section(
+              "",
+              TextStyle {
+                font: server.load("pixelify.ttf"),
+                font_size: 40.0,
+                color: BLACK.into(),
+              },
+            ),
+            DateText,
+          ));
+          toolbar.spawn((
+            TextBundle::from_section(
+              "",
+              TextStyle {
+                font: server.load("pixelify.ttf"),
+                font_size: 40.0,
+                color: BLACK.into(),
+              },
+            ),
+            ForecastText,
+          ));
+          toolbar.spawn((
+            TextBundle::from_section(
+              "0g",
+              TextStyle {
+                font: server.load("pixelify.ttf"),
+                font_size: 40.0,
+                color: BLACK.into(),
+              },
+            ),
+            WalletText,
+          ));
+          toolbar
+            .spawn(NodeBundle {
+              style: Style {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(4.),
+                ..default()
+              },
+              ..default()
+            })
+            .with_children(|hotbar| {
+              for i in 0..HOTBAR_SLOTS {
+                hotbar
+                  .spawn((
+                    NodeBundle {
+                      style: Style {
+                        width: Val::Px(56.),
+                        height: Val::Px(56.),
+                        border: UiRect::all(Val::Px(4.)),
+                        ..default()
+                      },
+                      border_color: BorderColor(BLACK.into()),
+                      ..default()
+                    },
+                    HotbarSlot(i),
+                  ))
+                  .with_children(|slot| {
+                    slot.spawn((
+                      NodeBundle {
+                        style: Style {
+                          width: Val::Percent(100.),
+                          height: Val::Percent(100.),
+                          ..default()
+                        },
+                        visibility: Visibility::Hidden,
+                        ..default()
+                      },
+                      UiImage::default(),
+                      HotbarIcon(i),
+                    ));
+                    slot.spawn((
+                      TextBundle::from_section(
+                        "",
+                        TextStyle {
+                          font: server.load("pixelify.ttf"),
+                          font_size: 20.0,
+                          color: BLACK.into(),
+                        },
+                      )
+                      .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        right: Val::Px(0.),
+                        bottom: Val::Px(0.),
+                        ..default()
+                      }),
+                      HotbarCount(i),
+                    ));
+                  });
+              }
+            });
+          toolbar.spawn((
+            TextBundle::from_section(
+              "",
+              TextStyle {
+                font: server.load("pixelify.ttf"),
+                font_size: 40.0,
+                color: BLACK.into(),
+              },
+            ),
+            VariantText,
+          ));
+          toolbar.spawn((
+            TextBundle::from_section(
+              "",
+              TextStyle {
+                font: server.load("pixelify.ttf"),
+                font_size: 40.0,
+                color: RED.into(),
+              },
+            ),
+            NoticeText(Timer::from_seconds(NOTICE_SECS, TimerMode::Once)),
+          ));
+        });
     });
 
-  commands.spawn((
-    Selector,
-    SpriteBundle {
-      texture: server.load("selector.png"),
-      ..default()
-    },
-  ));
+  // One selector per tile the largest tool tier can reach
+  for _ in 0..ToolTier::MAX_AREA {
+    commands.spawn((
+      Selector,
+      SpriteBundle {
+        texture: server.load("selector.png"),
+        visibility: Visibility::Hidden,
+        ..default()
+      },
+    ));
+  }
 }
 
 fn update_arability(
@@ -124,20 +333,174 @@ fn update_arability(
   }
 }
 
+fn update_variant(
+  items: ItemData,
+  tiers: Res<ToolTiers>,
+  can: Res<WateringCan>,
+  q_inventory: Query<&Inventory, With<Player>>,
+  mut q_text: Query<&mut Text, With<VariantText>>,
+) {
+  let mut text = q_text.single_mut();
+  let Some(stack) = q_inventory
+    .get_single()
+    .ok()
+    .and_then(|inventory| inventory.selected())
+  else {
+    text.sections[0].value.clear();
+    return;
+  };
+  let name = items
+    .info(&stack.item)
+    .map_or(stack.item.0.as_str(), |info| info.name.as_str());
+  text.sections[0].value = match items.tool(&stack.item) {
+    Some(tool @ (Tool::Cultivate | Tool::Harvest)) => {
+      format!("{} {}", tiers.get(&tool).name(), name)
+    }
+    Some(tool @ Tool::Water) => format!(
+      "{} {} {}/{}",
+      tiers.get(&tool).name(),
+      name,
+      can.charges,
+      can.capacity
+    ),
+    _ => name.to_owned(),
+  };
+}
+
+fn update_hotbar(
+  items: ItemData,
+  server: Res<AssetServer>,
+  q_inventory: Query<&Inventory, (With<Player>, Changed<Inventory>)>,
+  mut q_slots: Query<(&HotbarSlot, &mut BorderColor)>,
+  mut q_icons: Query<(&HotbarIcon, &mut UiImage, &mut Visibility)>,
+  mut q_counts: Query<(&HotbarCount, &mut Text)>,
+) {
+  let Ok(inventory) = q_inventory.get_single() else {
+    return;
+  };
+  for (slot, mut border) in &mut q_slots {
+    let selected = slot.0 == inventory.selected_index();
+    border.0 = if selected { GOLD } else { BLACK }.into();
+  }
+  for (icon, mut image, mut visibility) in &mut q_icons {
+    match inventory
+      .slot(icon.0)
+      .and_then(|stack| items.info(&stack.item))
+    {
+      Some(info) => {
+        image.texture = server.load(&info.icon);
+        *visibility = Visibility::Inherited;
+      }
+      None => *visibility = Visibility::Hidden,
+    }
+  }
+  for (count, mut text) in &mut q_counts {
+    text.sections[0].value = match inventory.slot(count.0) {
+      Some(stack) if stack.amount > 1 => stack.amount.to_string(),
+      _ => String::new(),
+    };
+  }
+}
+
+fn update_notice(
+  time: Res<Time>,
+  mut ev_notice: EventReader<Notice>,
+  mut q_text: Query<(&mut Text, &mut NoticeText)>,
+) {
+  let (mut text, mut notice) = q_text.single_mut();
+  if let Some(Notice(message)) = ev_notice.read().last() {
+    text.sections[0].value.clone_from(message);
+    notice.0.reset();
+  }
+  if notice.0.tick(time.delta()).just_finished() {
+    text.sections[0].value.clear();
+  }
+}
+
+fn update_energy(
+  energy: Res<Energy>,
+  mut q_fill: Query<&mut Style, With<EnergyFill>>,
+) {
+  if !energy.is_changed() {
+    return;
+  }
+  let mut style = q_fill.single_mut();
+  style.width = Val::Percent(energy.fraction() * 100.);
+}
+
+fn update_wallet(
+  wallet: Res<Wallet>,
+  mut q_text: Query<&mut Text, With<WalletText>>,
+) {
+  if !wallet.is_changed() {
+    return;
+  }
+  let mut text = q_text.single_mut();
+  text.sections[0].value = format!("{}g", wallet.money);
+}
+
+fn update_date(
+  day: Res<DayCycle>,
+  mut q_text: Query<&mut Text, With<DateText>>,
+) {
+  let mut text = q_text.single_mut();
+  let date = day.date();
+  if text.sections[0].value != date {
+    text.sections[0].value = date;
+  }
+}
+
+fn update_forecast(
+  forecast: Res<Forecast>,
+  mut q_text: Query<&mut Text, With<ForecastText>>,
+) {
+  let mut text = q_text.single_mut();
+  let value = format!("{:?}, tomorrow {:?}", forecast.today, forecast.tomorrow);
+  if text.sections[0].value != value {
+    text.sections[0].value = value;
+  }
+}
+
 fn update_selector(
   time: Res<Time>,
+  items: ItemData,
+  tiers: Res<ToolTiers>,
   interface: Res<Interface>,
-  mut selector: Query<(&mut Transform, &mut Sprite), With<Selector>>,
+  q_player: Query<(&Direction, &GlobalTransform, &Inventory), With<Player>>,
+  mut selectors: Query<
+    (&mut Transform, &mut Sprite, &mut Visibility),
+    With<Selector>,
+  >,
 ) {
-  let (mut transform, mut sprite) = selector.single_mut();
-  let selector_pos = interface.selector_pos();
-  transform.translation.x = selector_pos.x;
-  transform.translation.y = selector_pos.y;
-  transform.translation.z = 5.;
+  let Ok((facing, player, inventory)) = q_player.get_single() else {
+    return;
+  };
+  // Nothing to aim when the held item is not a tool
+  let area = inventory
+    .selected()
+    .and_then(|stack| items.tool(&stack.item))
+    .map_or_else(Vec::new, |tool| {
+      tiers.area(&tool, interface.cursor_grid_coords(), facing)
+    });
+  let alpha = (time.elapsed_seconds() * 2.).sin().abs();
 
-  sprite
-    .color
-    .set_alpha((time.elapsed_seconds() * 2.).sin().abs());
+  for (i, (mut transform, mut sprite, mut visibility)) in
+    selectors.iter_mut().enumerate()
+  {
+    match area.get(i) {
+      Some(&coords) => {
+        transform.translation = tile_center(coords).extend(5.);
+        sprite.color = if in_reach(player, coords) {
+          Color::WHITE
+        } else {
+          OUT_OF_REACH_TINT
+        };
+        sprite.color.set_alpha(alpha);
+        *visibility = Visibility::Visible;
+      }
+      None => *visibility = Visibility::Hidden,
+    }
+  }
 }
 
 fn update_cursor(
@@ -157,22 +520,35 @@ fn update_cursor(
   }
 }
 
-fn tool_cycle(
-  mut tool: ResMut<Tool>,
-  server: Res<AssetServer>,
-  mut icon: Query<&mut UiImage, With<ToolIcon>>,
+fn hotbar_select(
   kbd: Res<ButtonInput<KeyCode>>,
+  mut ev_wheel: EventReader<MouseWheel>,
+  mut q_inventory: Query<&mut Inventory, With<Player>>,
 ) {
-  if kbd.just_pressed(KeyCode::Tab) {
-    if kbd.pressed(KeyCode::ShiftLeft) {
-      tool.rev_cycle();
-    } else {
-      tool.cycle();
-    }
-    let texture = tool.texture(&server);
-    icon
-      .par_iter_mut()
-      .for_each(move |mut ui_img| ui_img.texture = texture.clone());
+  let scroll: f32 = ev_wheel.read().map(|ev| ev.y).sum();
+  let Ok(mut inventory) = q_inventory.get_single_mut() else {
+    return;
+  };
+  if let Some(index) = HOTBAR_KEYS.iter().position(|&key| kbd.just_pressed(key))
+  {
+    inventory.select(index);
+  } else if scroll > 0. {
+    inventory.scroll(-1);
+  } else if scroll < 0. {
+    inventory.scroll(1);
+  }
+}
+
+// Clicks on an egui window must not reach the world behind it
+fn block_world_clicks(
+  mut contexts: EguiContexts,
+  mut mouse: ResMut<ButtonInput<MouseButton>>,
+) {
+  if contexts
+    .try_ctx_mut()
+    .is_some_and(|ctx| ctx.is_pointer_over_area())
+  {
+    mouse.reset_all();
   }
 }
 
@@ -182,10 +558,24 @@ impl Plugin for InterfacePlugin {
   fn build(&self, app: &mut App) {
     app
       .insert_resource(Interface { cursor: default() })
+      .add_event::<Notice>()
       .add_systems(Startup, setup)
+      .add_systems(PreUpdate, block_world_clicks.after(InputSystem))
       .add_systems(
         Update,
-        (update_cursor, update_arability, update_selector, tool_cycle),
+        (
+          update_cursor,
+          update_arability,
+          update_variant,
+          update_energy,
+          update_wallet,
+          update_date,
+          update_forecast,
+          update_notice,
+          update_selector,
+          update_hotbar,
+          hotbar_select,
+        ),
       );
   }
 }
@@ -198,9 +588,6 @@ impl Interface {
       y: v.y as i32,
     }
   }
-  pub fn selector_pos(&self) -> Vec2 {
-    (self.cursor / 16.).ceil() * 16. - 8.
-  }
   pub fn selected_grass(&self, world_index: &WorldIndex) -> Option<Entity> {
     let curs_coords = self.cursor_grid_coords();
     world_index
@@ -213,3 +600,13 @@ impl Interface {
     world_index.get(curs_coords).map(|(_, typ)| typ)
   }
 }
+
+impl Pointer<'_> {
+  // Tile under the cursor if the button was just pressed
+  pub fn clicked(&self, button: MouseButton) -> Option<GridCoords> {
+    self
+      .mouse
+      .just_pressed(button)
+      .then(|| self.interface.cursor_grid_coords())
+  }
+}
@@ -0,0 +1,313 @@
+use bevy::{
+  app::{App, Plugin, Startup, Update},
+  asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets,
+    AsyncReadExt, Handle, LoadContext,
+  },
+  ecs::system::SystemParam,
+  prelude::{Commands, Component, Entity, Query, Res, Resource, With},
+  reflect::TypePath,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+  daycycle::Season, machines::MachineInfo, sprinkler::SprinklerPattern,
+  tools::Tool,
+};
+
+pub const HOTBAR_SLOTS: usize = 10;
+const INVENTORY_SLOTS: usize = 30;
+const DEFAULT_MAX_STACK: u32 = 99;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(transparent)]
+pub struct Item(pub String);
+
+#[derive(Clone, Deserialize)]
+pub struct ItemAmount {
+  pub item: Item,
+  pub amount: u32,
+}
+
+#[derive(Deserialize)]
+pub struct ItemInfo {
+  pub id: Item,
+  pub name: String,
+  pub icon: String,
+  #[serde(default = "default_max_stack")]
+  pub max_stack: u32,
+  // Base money paid per item when shipped, 0 if it can't be sold
+  #[serde(default)]
+  pub price: u32,
+  // Seasons in which the item sells for more
+  #[serde(default)]
+  pub demand: Vec<Season>,
+  // Tool used while the item is held
+  #[serde(default)]
+  pub tool: Option<Tool>,
+  // Sprinkler placed when the item is used
+  #[serde(default)]
+  pub sprinkler: Option<SprinklerPattern>,
+  // Machine placed when the item is used
+  #[serde(default)]
+  pub machine: Option<MachineInfo>,
+  // Amount the player starts the game with
+  #[serde(default)]
+  pub starter: u32,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct ItemRegistry {
+  items: Vec<ItemInfo>,
+}
+
+#[derive(Resource)]
+pub struct Items {
+  registry: Handle<ItemRegistry>,
+}
+
+#[derive(SystemParam)]
+pub struct ItemData<'w> {
+  items: Res<'w, Items>,
+  registries: Res<'w, Assets<ItemRegistry>>,
+}
+
+#[derive(Clone)]
+pub struct Stack {
+  pub item: Item,
+  pub amount: u32,
+}
+
+#[derive(Component)]
+pub struct Inventory {
+  slots: Vec<Option<Stack>>,
+  // Hotbar slot whose item is in hand
+  selected: usize,
+}
+
+// Marks an inventory that still has to receive the starter items
+#[derive(Component, Default)]
+pub struct StarterItems;
+
+#[derive(Default)]
+struct ItemRegistryLoader;
+
+#[derive(Debug, Error)]
+enum ItemRegistryLoaderError {
+  #[error("Could not read item definitions: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Could not parse item definitions: {0}")]
+  Ron(#[from] ron::error::SpannedError),
+  #[error("Machine {0} has a recipe taking {1} days")]
+  RecipeDays(String, f32),
+}
+
+impl AssetLoader for ItemRegistryLoader {
+  type Asset = ItemRegistry;
+  type Settings = ();
+  type Error = ItemRegistryLoaderError;
+
+  async fn load<'a>(
+    &'a self,
+    reader: &'a mut Reader<'_>,
+    _settings: &'a (),
+    _load_context: &'a mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    let registry: ItemRegistry = ron::de::from_bytes(&bytes)?;
+    for info in &registry.items {
+      let mut recipes =
+        info.machine.iter().flat_map(|machine| &machine.recipes);
+      if let Some(recipe) = recipes.find(|recipe| recipe.days <= 0.) {
+        return Err(ItemRegistryLoaderError::RecipeDays(
+          info.id.0.clone(),
+          recipe.days,
+        ));
+      }
+    }
+    Ok(registry)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["items.ron"]
+  }
+}
+
+fn default_max_stack() -> u32 {
+  DEFAULT_MAX_STACK
+}
+
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
+  commands.insert_resource(Items {
+    registry: server.load("items.ron"),
+  });
+}
+
+fn give_starter_items(
+  items: ItemData,
+  mut q_inventory: Query<(Entity, &mut Inventory), With<StarterItems>>,
+  mut commands: Commands,
+) {
+  let Some(registry) = items.registry() else {
+    return;
+  };
+  for (entity, mut inventory) in &mut q_inventory {
+    for info in registry.items.iter().filter(|info| info.starter > 0) {
+      inventory.add(info.id.clone(), info.starter, info.max_stack);
+    }
+    commands.entity(entity).remove::<StarterItems>();
+  }
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<ItemRegistry>()
+      .init_asset_loader::<ItemRegistryLoader>()
+      .add_systems(Startup, setup)
+      .add_systems(Update, give_starter_items);
+  }
+}
+
+impl ItemRegistry {
+  pub fn items(&self) -> impl Iterator<Item = &ItemInfo> {
+    self.items.iter()
+  }
+
+  pub fn get(&self, item: &Item) -> Option<&ItemInfo> {
+    self.items.iter().find(|info| info.id == *item)
+  }
+}
+
+impl ItemData<'_> {
+  pub fn registry(&self) -> Option<&ItemRegistry> {
+    self.registries.get(&self.items.registry)
+  }
+
+  pub fn info(&self, item: &Item) -> Option<&ItemInfo> {
+    self.registry().and_then(|registry| registry.get(item))
+  }
+
+  pub fn max_stack(&self, item: &Item) -> u32 {
+    self
+      .info(item)
+      .map_or(DEFAULT_MAX_STACK, |info| info.max_stack)
+  }
+
+  pub fn tool(&self, item: &Item) -> Option<Tool> {
+    self.info(item).and_then(|info| info.tool)
+  }
+}
+
+impl Default for Inventory {
+  fn default() -> Self {
+    Self {
+      slots: vec![None; INVENTORY_SLOTS],
+      selected: 0,
+    }
+  }
+}
+
+impl Inventory {
+  // Fills matching stacks first, then empty slots, returning what did not fit
+  pub fn add(&mut self, item: Item, mut amount: u32, max_stack: u32) -> u32 {
+    for stack in self.slots.iter_mut().flatten() {
+      if amount == 0 {
+        break;
+      }
+      if stack.item == item && stack.amount < max_stack {
+        let moved = amount.min(max_stack - stack.amount);
+        stack.amount += moved;
+        amount -= moved;
+      }
+    }
+    for slot in self.slots.iter_mut().filter(|slot| slot.is_none()) {
+      if amount == 0 {
+        break;
+      }
+      let moved = amount.min(max_stack);
+      *slot = Some(Stack {
+        item: item.clone(),
+        amount: moved,
+      });
+      amount -= moved;
+    }
+    amount
+  }
+
+  pub fn fits(&self, item: &Item, amount: u32, max_stack: u32) -> bool {
+    let room: u32 = self
+      .slots
+      .iter()
+      .map(|slot| match slot {
+        Some(stack) if stack.item == *item => {
+          max_stack.saturating_sub(stack.amount)
+        }
+        Some(_) => 0,
+        None => max_stack,
+      })
+      .sum();
+    room >= amount
+  }
+
+  pub fn count(&self, item: &Item) -> u32 {
+    self
+      .slots
+      .iter()
+      .flatten()
+      .filter(|stack| stack.item == *item)
+      .map(|stack| stack.amount)
+      .sum()
+  }
+
+  pub fn take(&mut self, item: &Item, mut amount: u32) -> bool {
+    if self.count(item) < amount {
+      return false;
+    }
+    for slot in &mut self.slots {
+      let Some(stack) = slot.as_mut().filter(|stack| stack.item == *item)
+      else {
+        continue;
+      };
+      let taken = amount.min(stack.amount);
+      stack.amount -= taken;
+      amount -= taken;
+      if stack.amount == 0 {
+        *slot = None;
+      }
+      if amount == 0 {
+        break;
+      }
+    }
+    true
+  }
+
+  pub fn take_selected(&mut self) -> Option<Stack> {
+    self.slots[self.selected].take()
+  }
+
+  pub fn slot(&self, index: usize) -> Option<&Stack> {
+    self.slots.get(index).and_then(Option::as_ref)
+  }
+
+  pub fn selected(&self) -> Option<&Stack> {
+    self.slot(self.selected)
+  }
+
+  pub fn selected_index(&self) -> usize {
+    self.selected
+  }
+
+  pub fn select(&mut self, index: usize) {
+    self.selected = index.min(HOTBAR_SLOTS - 1);
+  }
+
+  pub fn scroll(&mut self, delta: i32) {
+    self.selected =
+      (self.selected as i32 + delta).rem_euclid(HOTBAR_SLOTS as i32) as usize;
+  }
+}
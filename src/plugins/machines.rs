@@ -0,0 +1,214 @@
+use bevy::{
+  app::{App, Plugin, Update},
+  asset::AssetServer,
+  color::Color,
+  hierarchy::{BuildChildren, Children},
+  math::Vec2,
+  prelude::{
+    default, Added, Bundle, Commands, Component, Entity, EventWriter,
+    GlobalTransform, MouseButton, Query, Res, Transform, Visibility, With,
+  },
+  sprite::{Anchor, Sprite, SpriteBundle},
+};
+use bevy_ecs_ldtk::GridCoords;
+use serde::Deserialize;
+
+use super::{
+  daycycle::DayCycle,
+  drops::DropEvent,
+  interface::{Notice, Pointer},
+  inventory::{Inventory, Item, ItemAmount, ItemData},
+  player::{in_reach, Player},
+  world::tile_center,
+};
+
+const PROGRESS_WIDTH: f32 = 12.;
+const PROGRESS_COLOR: Color = Color::srgb(0.95, 0.75, 0.2);
+const READY_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+
+#[derive(Deserialize)]
+pub struct MachineRecipe {
+  pub input: ItemAmount,
+  pub output: ItemAmount,
+  // In-game days until the output is ready
+  pub days: f32,
+}
+
+#[derive(Deserialize)]
+pub struct MachineInfo {
+  pub recipes: Vec<MachineRecipe>,
+}
+
+struct Job {
+  output: ItemAmount,
+  // Times in days since the start of the game
+  started: f32,
+  ready_at: f32,
+}
+
+#[derive(Component)]
+pub struct Machine {
+  // Item the machine was placed from, which holds its recipes
+  kind: Item,
+  job: Option<Job>,
+}
+
+#[derive(Component)]
+struct MachineProgress;
+
+#[derive(Bundle)]
+pub struct MachineBundle {
+  machine: Machine,
+  coords: GridCoords,
+  sprite: SpriteBundle,
+}
+
+fn spawn_progress(
+  q_machines: Query<Entity, Added<Machine>>,
+  mut commands: Commands,
+) {
+  for entity in &q_machines {
+    commands.entity(entity).with_children(|parent| {
+      parent.spawn((
+        MachineProgress,
+        SpriteBundle {
+          sprite: Sprite {
+            anchor: Anchor::CenterLeft,
+            ..default()
+          },
+          transform: Transform::from_xyz(-PROGRESS_WIDTH / 2., 9., 1.),
+          visibility: Visibility::Hidden,
+          ..default()
+        },
+      ));
+    });
+  }
+}
+
+fn use_machine(
+  items: ItemData,
+  day: Res<DayCycle>,
+  pointer: Pointer,
+  mut q_player: Query<(&GlobalTransform, &mut Inventory), With<Player>>,
+  mut q_machines: Query<(&GridCoords, &mut Machine)>,
+  mut ev_drop: EventWriter<DropEvent>,
+  mut ev_notice: EventWriter<Notice>,
+) {
+  let Some(target) = pointer.clicked(MouseButton::Left) else {
+    return;
+  };
+  let Some((coords, mut machine)) =
+    q_machines.iter_mut().find(|(c, _)| **c == target)
+  else {
+    return;
+  };
+  let Ok((player, mut inventory)) = q_player.get_single_mut() else {
+    return;
+  };
+  if !in_reach(player, target) {
+    return;
+  }
+  let Some(info) = items.info(&machine.kind) else {
+    return;
+  };
+  let now = day.elapsed();
+
+  match &machine.job {
+    Some(job) if now >= job.ready_at => {
+      ev_drop.send(DropEvent {
+        at: tile_center(*coords),
+        item: job.output.item.clone(),
+        amount: job.output.amount,
+      });
+      machine.job = None;
+    }
+    Some(_) => {
+      ev_notice.send(Notice(format!("{} is still working", info.name)));
+    }
+    None => {
+      let Some(held) = inventory.selected().map(|stack| stack.item.clone())
+      else {
+        return;
+      };
+      let recipe = info
+        .machine
+        .iter()
+        .flat_map(|machine| &machine.recipes)
+        .find(|recipe| recipe.input.item == held);
+      let Some(recipe) = recipe else {
+        ev_notice.send(Notice(format!("{} can't process that", info.name)));
+        return;
+      };
+      if !inventory.take(&held, recipe.input.amount) {
+        let name = items.info(&held).map_or(&held.0, |info| &info.name);
+        ev_notice.send(Notice(format!("Needs {} {name}", recipe.input.amount)));
+        return;
+      }
+      machine.job = Some(Job {
+        output: recipe.output.clone(),
+        started: now,
+        ready_at: now + recipe.days,
+      });
+    }
+  }
+}
+
+fn update_progress(
+  day: Res<DayCycle>,
+  q_machines: Query<(&Machine, &Children)>,
+  mut q_progress: Query<(&mut Sprite, &mut Visibility), With<MachineProgress>>,
+) {
+  let now = day.elapsed();
+  for (machine, children) in &q_machines {
+    let mut iter = q_progress.iter_many_mut(children);
+    while let Some((mut sprite, mut visibility)) = iter.fetch_next() {
+      let Some(job) = &machine.job else {
+        *visibility = Visibility::Hidden;
+        continue;
+      };
+      let fraction =
+        ((now - job.started) / (job.ready_at - job.started)).clamp(0., 1.);
+      sprite.custom_size = Some(Vec2::new(PROGRESS_WIDTH * fraction, 2.));
+      sprite.color = if fraction >= 1. {
+        READY_COLOR
+      } else {
+        PROGRESS_COLOR
+      };
+      *visibility = Visibility::Inherited;
+    }
+  }
+}
+
+pub struct MachinesPlugin;
+
+impl Plugin for MachinesPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, (spawn_progress, use_machine, update_progress));
+  }
+}
+
+impl Machine {
+  // Whether something is processing or waiting to be collected
+  pub fn busy(&self) -> bool {
+    self.job.is_some()
+  }
+}
+
+impl MachineBundle {
+  pub fn new(
+    kind: Item,
+    texture: &str,
+    coords: GridCoords,
+    server: &AssetServer,
+  ) -> Self {
+    Self {
+      machine: Machine { kind, job: None },
+      coords,
+      sprite: SpriteBundle {
+        texture: server.load(texture.to_owned()),
+        transform: Transform::from_translation(tile_center(coords).extend(4.)),
+        ..default()
+      },
+    }
+  }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use bevy::{
+  app::{App, Plugin, Update},
+  prelude::{
+    EventReader, IntoSystemConfigs, IntoSystemSetConfigs, Res, ResMut,
+    Resource, SystemSet,
+  },
+};
+use rand::{thread_rng, Rng};
+
+use super::{
+  daycycle::{DayCycle, DayTick, NewDayEvent, Season},
+  inventory::{Item, ItemData, ItemInfo},
+};
+
+// Price drop caused by each shipped item
+const SATURATION_PER_ITEM: f32 = 0.01;
+const MAX_SATURATION: f32 = 0.6;
+// Share of the price drop that wears off each day
+const RECOVERY: f32 = 0.25;
+// Price raise while an item is in demand
+const DEMAND_BONUS: f32 = 0.3;
+// Largest random price change from one day to the next
+const DAILY_SWING: f32 = 0.1;
+
+// Prices change in this set, after the day's shipments were sold
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct PriceUpdate;
+
+#[derive(Resource)]
+pub struct Market {
+  season: Season,
+  // How far shipping pushed each price down, from 0 to MAX_SATURATION
+  saturation: HashMap<Item, f32>,
+  // Today's random price factor per item
+  swing: HashMap<Item, f32>,
+  // Items shipped since the last price update
+  shipped: HashMap<Item, u32>,
+}
+
+fn update_prices(
+  items: ItemData,
+  day: Res<DayCycle>,
+  mut market: ResMut<Market>,
+  mut ev_newday: EventReader<NewDayEvent>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  let Some(registry) = items.registry() else {
+    return;
+  };
+  let market = &mut *market;
+  market.season = day.season();
+
+  for saturation in market.saturation.values_mut() {
+    *saturation *= 1. - RECOVERY;
+  }
+  for (item, amount) in market.shipped.drain() {
+    let saturation = market.saturation.entry(item).or_default();
+    *saturation =
+      (*saturation + amount as f32 * SATURATION_PER_ITEM).min(MAX_SATURATION);
+  }
+
+  let mut rng = thread_rng();
+  market.swing = registry
+    .items()
+    .filter(|info| info.price > 0)
+    .map(|info| {
+      let swing = rng.gen_range(1. - DAILY_SWING..=1. + DAILY_SWING);
+      (info.id.clone(), swing)
+    })
+    .collect();
+}
+
+pub struct MarketPlugin;
+
+impl Plugin for MarketPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_resource::<Market>()
+      .configure_sets(Update, PriceUpdate.after(DayTick))
+      .add_systems(Update, update_prices.in_set(PriceUpdate));
+  }
+}
+
+impl Default for Market {
+  fn default() -> Self {
+    Self {
+      season: Season::Spring,
+      saturation: HashMap::new(),
+      swing: HashMap::new(),
+      shipped: HashMap::new(),
+    }
+  }
+}
+
+impl Market {
+  // Records a sale, which lowers the item's price from the next day on
+  pub fn ship(&mut self, item: &Item, amount: u32) {
+    *self.shipped.entry(item.clone()).or_default() += amount;
+  }
+
+  // Today's price relative to the item's base price
+  pub fn factor(&self, info: &ItemInfo) -> f32 {
+    let saturation = self.saturation.get(&info.id).copied().unwrap_or(0.);
+    let swing = self.swing.get(&info.id).copied().unwrap_or(1.);
+    let demand = if info.demand.contains(&self.season) {
+      1. + DEMAND_BONUS
+    } else {
+      1.
+    };
+    (1. - saturation) * swing * demand
+  }
+
+  // Money paid per item today, 0 if it can't be sold
+  pub fn price(&self, info: &ItemInfo) -> u32 {
+    if info.price == 0 {
+      return 0;
+    }
+    ((info.price as f32 * self.factor(info)).round() as u32).max(1)
+  }
+}
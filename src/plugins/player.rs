@@ -1,6 +1,11 @@
 use std::time::Duration;
 
-use super::controls::{Direction, PhysicsControlsBundle};
+use super::{
+  controls::{Direction, PhysicsControlsBundle},
+  inventory::{Inventory, Item, StarterItems},
+  tools::Tool,
+  world::tile_center,
+};
 use crate::components::physics::{Acceleration, PhysicsBundle};
 use avian2d::prelude::{
   AngularVelocity, Collider, LinearDamping, Mass, PhysicsSchedule,
@@ -11,19 +16,39 @@ use bevy::{
   input::ButtonInput,
   math::Vec3,
   prelude::{
-    default, Bundle, Component, IntoSystemConfigs, KeyCode, Query, Res,
-    Transform, With,
+    default, Bundle, Component, GlobalTransform, IntoSystemConfigs, KeyCode,
+    Query, Res, Transform, With,
   },
   sprite::{SpriteBundle, TextureAtlas},
   time::{Time, Timer, TimerMode},
 };
-use bevy_ecs_ldtk::{app::LdtkEntityAppExt, LdtkEntity, LdtkSpriteSheetBundle};
+use bevy_ecs_ldtk::{
+  app::LdtkEntityAppExt, GridCoords, LdtkEntity, LdtkSpriteSheetBundle,
+};
 
 const ANIM_FPS: f32 = 12.;
+// Max distance from the player to the center of a tile it can act on
+const REACH: f32 = 40.;
+// The tool lands at the end of the swing, followed by a short cooldown
+const TOOL_SWING_SECS: f32 = 0.25;
+const TOOL_COOLDOWN_SECS: f32 = 0.15;
+// Rows of the sprite sheet holding the raised and striking tool frames
+const TOOL_RAISE_PHASE: usize = 12;
+const TOOL_STRIKE_PHASE: usize = 16;
 
 #[derive(Default, Component)]
 pub struct Player;
 
+// Present on the player while a tool is being used, which locks movement
+#[derive(Component)]
+pub struct ToolUse {
+  pub tool: Tool,
+  pub item: Item,
+  pub target: GridCoords,
+  applied: bool,
+  timer: Timer,
+}
+
 #[derive(Component)]
 struct AnimationConfig {
   phase: usize,
@@ -33,6 +58,8 @@ struct AnimationConfig {
 #[derive(Bundle, LdtkEntity)]
 struct PlayerBundle {
   marker: Player,
+  inventory: Inventory,
+  starter: StarterItems,
   controls: PhysicsControlsBundle,
   #[sprite_sheet_bundle]
   sprite: LdtkSpriteSheetBundle,
@@ -43,6 +70,8 @@ impl Default for PlayerBundle {
   fn default() -> Self {
     Self {
       marker: default(),
+      inventory: default(),
+      starter: default(),
       sprite: LdtkSpriteSheetBundle {
         sprite_bundle: SpriteBundle {
           transform: Transform {
@@ -86,12 +115,21 @@ impl Default for PlayerBundle {
 fn apply_texture(
   time: Res<Time>,
   mut q_player: Query<
-    (&mut TextureAtlas, &Direction, &mut AnimationConfig),
+    (
+      &mut TextureAtlas,
+      &Direction,
+      &mut AnimationConfig,
+      Option<&ToolUse>,
+    ),
     With<Player>,
   >,
   kbd: Res<ButtonInput<KeyCode>>,
 ) {
-  for (mut atlas, dir, mut anim) in &mut q_player {
+  for (mut atlas, dir, mut anim, tool_use) in &mut q_player {
+    if let Some(tool_use) = tool_use {
+      atlas.index = dir.atlas_index() + tool_use.phase();
+      continue;
+    }
     anim.timer.tick(time.delta());
     let moving = kbd.pressed(KeyCode::KeyA)
       || kbd.pressed(KeyCode::KeyD)
@@ -117,6 +155,54 @@ fn cancel_angular_change(
   }
 }
 
+impl ToolUse {
+  pub fn new(tool: Tool, item: Item, target: GridCoords) -> Self {
+    Self {
+      tool,
+      item,
+      target,
+      applied: false,
+      timer: Timer::from_seconds(
+        TOOL_SWING_SECS + TOOL_COOLDOWN_SECS,
+        TimerMode::Once,
+      ),
+    }
+  }
+
+  pub fn tick(&mut self, delta: Duration) {
+    self.timer.tick(delta);
+  }
+
+  // True exactly once, when the swing reaches the target
+  pub fn strike(&mut self) -> bool {
+    if self.applied || self.timer.elapsed_secs() < TOOL_SWING_SECS {
+      return false;
+    }
+    self.applied = true;
+    true
+  }
+
+  pub fn finished(&self) -> bool {
+    self.timer.finished()
+  }
+
+  fn phase(&self) -> usize {
+    if self.timer.elapsed_secs() < TOOL_SWING_SECS / 2. {
+      TOOL_RAISE_PHASE
+    } else {
+      TOOL_STRIKE_PHASE
+    }
+  }
+}
+
+pub fn in_reach(player: &GlobalTransform, coords: GridCoords) -> bool {
+  player
+    .translation()
+    .truncate()
+    .distance(tile_center(coords))
+    <= REACH
+}
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
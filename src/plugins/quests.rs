@@ -0,0 +1,450 @@
+use avian2d::prelude::{Collider, RigidBody};
+use bevy::{
+  app::{App, Plugin, Startup, Update},
+  asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets,
+    AsyncReadExt, Handle, LoadContext,
+  },
+  input::ButtonInput,
+  prelude::{
+    Bundle, Commands, Component, EventReader, EventWriter, GlobalTransform,
+    IntoSystemConfigs, KeyCode, MouseButton, Query, Res, ResMut, Resource,
+    With,
+  },
+  reflect::TypePath,
+  sprite::SpriteBundle,
+};
+use bevy_ecs_ldtk::{app::LdtkEntityAppExt, GridCoords, LdtkEntity};
+use bevy_egui::{egui, EguiContexts};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+  daycycle::{DayCycle, DayTick, NewDayEvent, Season},
+  grass::OccupiesGround,
+  interface::{Interface, Notice},
+  inventory::{Inventory, Item, ItemData},
+  player::{in_reach, Player},
+  wallet::Trader,
+};
+
+#[derive(Deserialize)]
+pub struct QuestTemplate {
+  pub item: Item,
+  // Smallest and largest amount asked for
+  pub amount: (u32, u32),
+  // Days the player has to deliver after accepting
+  pub days: usize,
+  // Money paid per delivered item
+  pub reward: u32,
+  // Seasons the request is posted in, every season when empty
+  #[serde(default)]
+  pub seasons: Vec<Season>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct QuestTemplates {
+  // Requests posted on the board each morning
+  postings: usize,
+  // Requests the player can have accepted at once
+  max_accepted: usize,
+  templates: Vec<QuestTemplate>,
+}
+
+#[derive(Clone)]
+pub struct Quest {
+  pub item: Item,
+  pub amount: u32,
+  pub days: usize,
+  pub reward: u32,
+}
+
+struct AcceptedQuest {
+  quest: Quest,
+  // Last day the items can be delivered on
+  deadline: usize,
+}
+
+#[derive(Resource)]
+struct Quests {
+  templates: Handle<QuestTemplates>,
+  // Requests on the board, None until the first ones are posted
+  board: Option<Vec<Quest>>,
+  journal: Vec<AcceptedQuest>,
+  board_open: bool,
+  journal_open: bool,
+}
+
+#[derive(Default, Component)]
+pub struct RequestBoard;
+
+#[derive(Bundle, LdtkEntity)]
+struct RequestBoardBundle {
+  board: RequestBoard,
+  ground: OccupiesGround,
+  #[grid_coords]
+  coords: GridCoords,
+  #[sprite_bundle("request_board.png")]
+  sprite: SpriteBundle,
+  body: RigidBody,
+  collider: Collider,
+}
+
+impl Default for RequestBoardBundle {
+  fn default() -> Self {
+    Self {
+      board: RequestBoard,
+      ground: OccupiesGround,
+      coords: GridCoords::default(),
+      sprite: SpriteBundle::default(),
+      body: RigidBody::Static,
+      collider: Collider::rectangle(14., 14.),
+    }
+  }
+}
+
+#[derive(Default)]
+struct QuestTemplatesLoader;
+
+#[derive(Debug, Error)]
+enum QuestTemplatesLoaderError {
+  #[error("Could not read quest templates: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Could not parse quest templates: {0}")]
+  Ron(#[from] ron::error::SpannedError),
+  #[error("Request for {0} asks for at least {1} but at most {2}")]
+  Amount(String, u32, u32),
+}
+
+impl AssetLoader for QuestTemplatesLoader {
+  type Asset = QuestTemplates;
+  type Settings = ();
+  type Error = QuestTemplatesLoaderError;
+
+  async fn load<'a>(
+    &'a self,
+    reader: &'a mut Reader<'_>,
+    _settings: &'a (),
+    _load_context: &'a mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    let templates: QuestTemplates = ron::de::from_bytes(&bytes)?;
+    for template in &templates.templates {
+      let (min, max) = template.amount;
+      if min > max {
+        let item = template.item.0.clone();
+        return Err(QuestTemplatesLoaderError::Amount(item, min, max));
+      }
+    }
+    Ok(templates)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["quests.ron"]
+  }
+}
+
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
+  commands.insert_resource(Quests {
+    templates: server.load("quests.ron"),
+    board: None,
+    journal: Vec::new(),
+    board_open: false,
+    journal_open: false,
+  });
+}
+
+// Replaces the requests on the board every morning
+fn post_requests(
+  day: Res<DayCycle>,
+  templates: Res<Assets<QuestTemplates>>,
+  mut quests: ResMut<Quests>,
+  mut ev_newday: EventReader<NewDayEvent>,
+) {
+  let new_day = !ev_newday.is_empty();
+  ev_newday.clear();
+  if !new_day && quests.board.is_some() {
+    return;
+  }
+  let Some(templates) = templates.get(&quests.templates) else {
+    return;
+  };
+  quests.board = Some(templates.post(day.season()));
+}
+
+fn expire_quests(
+  items: ItemData,
+  day: Res<DayCycle>,
+  mut quests: ResMut<Quests>,
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut ev_notice: EventWriter<Notice>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  quests.journal.retain(|accepted| {
+    if accepted.deadline >= day.day {
+      return true;
+    }
+    let name = items
+      .info(&accepted.quest.item)
+      .map_or(accepted.quest.item.0.as_str(), |info| info.name.as_str());
+    ev_notice.send(Notice(format!("Request for {name} expired")));
+    false
+  });
+}
+
+fn open_board(
+  interface: Res<Interface>,
+  mouse: Res<ButtonInput<MouseButton>>,
+  q_player: Query<&GlobalTransform, With<Player>>,
+  q_boards: Query<&GridCoords, With<RequestBoard>>,
+  mut quests: ResMut<Quests>,
+) {
+  if !mouse.just_pressed(MouseButton::Left) {
+    return;
+  }
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  let target = interface.cursor_grid_coords();
+  if in_reach(player, target) && q_boards.iter().any(|coords| *coords == target)
+  {
+    quests.board_open = true;
+  }
+}
+
+// Closes the board once the player walks away from it
+fn close_board(
+  kbd: Res<ButtonInput<KeyCode>>,
+  q_player: Query<&GlobalTransform, With<Player>>,
+  q_boards: Query<&GridCoords, With<RequestBoard>>,
+  mut quests: ResMut<Quests>,
+) {
+  if !quests.board_open {
+    return;
+  }
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  if kbd.just_pressed(KeyCode::Escape)
+    || !q_boards.iter().any(|coords| in_reach(player, *coords))
+  {
+    quests.board_open = false;
+  }
+}
+
+fn toggle_journal(kbd: Res<ButtonInput<KeyCode>>, mut quests: ResMut<Quests>) {
+  if kbd.just_pressed(KeyCode::KeyJ) {
+    quests.journal_open = !quests.journal_open;
+  } else if kbd.just_pressed(KeyCode::Escape) {
+    quests.journal_open = false;
+  }
+}
+
+fn board_window(
+  mut contexts: EguiContexts,
+  mut quests: ResMut<Quests>,
+  templates: Res<Assets<QuestTemplates>>,
+  items: ItemData,
+  day: Res<DayCycle>,
+  trader: Trader,
+) {
+  if !quests.board_open {
+    return;
+  }
+  let Trader {
+    mut wallet,
+    mut q_inventory,
+    mut ev_notice,
+  } = trader;
+  let (Some(templates), Ok(mut inventory)) = (
+    templates.get(&quests.templates),
+    q_inventory.get_single_mut(),
+  ) else {
+    return;
+  };
+  let name = |item: &Item| {
+    items
+      .info(item)
+      .map_or(item.0.clone(), |info| info.name.clone())
+  };
+
+  let quests = &mut *quests;
+  let mut open = true;
+  egui::Window::new("Request board")
+    .open(&mut open)
+    .collapsible(false)
+    .resizable(false)
+    .show(contexts.ctx_mut(), |ui| {
+      ui.label("Posted");
+      egui::Grid::new("quest_postings")
+        .striped(true)
+        .show(ui, |ui| {
+          let can_accept = quests.journal.len() < templates.max_accepted;
+          let mut accepted = None;
+          for (i, quest) in quests.board.iter().flatten().enumerate() {
+            ui.label(format!(
+              "Deliver {} {} within {} days",
+              quest.amount,
+              name(&quest.item),
+              quest.days
+            ));
+            ui.label(format!("{}g", quest.reward));
+            if ui
+              .add_enabled(can_accept, egui::Button::new("Accept"))
+              .clicked()
+            {
+              accepted = Some(i);
+            }
+            ui.end_row();
+          }
+          if let Some(quest) = accepted
+            .and_then(|i| quests.board.as_mut().map(|board| board.remove(i)))
+          {
+            quests.journal.push(AcceptedQuest {
+              deadline: day.day + quest.days,
+              quest,
+            });
+          }
+        });
+      ui.separator();
+      ui.label("Accepted");
+      egui::Grid::new("quest_deliveries")
+        .striped(true)
+        .show(ui, |ui| {
+          let mut delivered = None;
+          for (i, accepted) in quests.journal.iter().enumerate() {
+            let quest = &accepted.quest;
+            let held = inventory.count(&quest.item);
+            ui.label(format!(
+              "{} {} ({held} held)",
+              quest.amount,
+              name(&quest.item)
+            ));
+            ui.label(format!("{}g", quest.reward));
+            if ui
+              .add_enabled(held >= quest.amount, egui::Button::new("Deliver"))
+              .clicked()
+            {
+              delivered = Some(i);
+            }
+            ui.end_row();
+          }
+          if let Some(i) = delivered {
+            let quest = quests.journal.remove(i).quest;
+            inventory.take(&quest.item, quest.amount);
+            wallet.earn(quest.reward);
+            ev_notice.send(Notice(format!(
+              "Request complete, earned {}g",
+              quest.reward
+            )));
+          }
+        });
+    });
+  quests.board_open &= open;
+}
+
+fn journal_window(
+  mut contexts: EguiContexts,
+  mut quests: ResMut<Quests>,
+  items: ItemData,
+  day: Res<DayCycle>,
+  q_inventory: Query<&Inventory, With<Player>>,
+) {
+  if !quests.journal_open {
+    return;
+  }
+  let Ok(inventory) = q_inventory.get_single() else {
+    return;
+  };
+
+  let mut open = true;
+  egui::Window::new("Journal")
+    .open(&mut open)
+    .collapsible(false)
+    .resizable(false)
+    .show(contexts.ctx_mut(), |ui| {
+      if quests.journal.is_empty() {
+        ui.label("No accepted requests");
+        return;
+      }
+      egui::Grid::new("journal").striped(true).show(ui, |ui| {
+        for accepted in &quests.journal {
+          let quest = &accepted.quest;
+          let name = items
+            .info(&quest.item)
+            .map_or(quest.item.0.as_str(), |info| info.name.as_str());
+          let held = inventory.count(&quest.item).min(quest.amount);
+          ui.label(format!("Deliver {} {name}", quest.amount));
+          ui.label(format!("{held}/{}", quest.amount));
+          ui.label(match accepted.deadline.saturating_sub(day.day) {
+            0 => "Due today".to_owned(),
+            1 => "1 day left".to_owned(),
+            left => format!("{left} days left"),
+          });
+          ui.label(format!("{}g", quest.reward));
+          ui.end_row();
+        }
+      });
+    });
+  quests.journal_open &= open;
+}
+
+pub struct QuestsPlugin;
+
+impl Plugin for QuestsPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<QuestTemplates>()
+      .init_asset_loader::<QuestTemplatesLoader>()
+      .register_ldtk_entity_for_layer::<RequestBoardBundle>(
+        "entities",
+        "request_board",
+      )
+      .add_systems(Startup, setup)
+      .add_systems(
+        Update,
+        (
+          post_requests.after(DayTick),
+          expire_quests.after(DayTick),
+          open_board,
+          close_board,
+          toggle_journal,
+          board_window,
+          journal_window,
+        ),
+      );
+  }
+}
+
+impl QuestTemplates {
+  // Picks the requests posted on the board for a day in `season`
+  pub fn post(&self, season: Season) -> Vec<Quest> {
+    let mut rng = thread_rng();
+    let available: Vec<&QuestTemplate> = self
+      .templates
+      .iter()
+      .filter(|template| {
+        template.seasons.is_empty() || template.seasons.contains(&season)
+      })
+      .collect();
+    available
+      .choose_multiple(&mut rng, self.postings)
+      .map(|template| {
+        let (min, max) = template.amount;
+        let amount = rng.gen_range(min..=max);
+        Quest {
+          item: template.item.clone(),
+          amount,
+          days: template.days,
+          reward: template.reward * amount,
+        }
+      })
+      .collect()
+  }
+}
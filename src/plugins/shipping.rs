@@ -0,0 +1,138 @@
+use avian2d::prelude::{Collider, RigidBody};
+use bevy::{
+  app::{App, Plugin, Update},
+  input::ButtonInput,
+  log::info,
+  prelude::{
+    Bundle, Component, EventReader, EventWriter, GlobalTransform,
+    IntoSystemConfigs, MouseButton, Query, Res, ResMut, With,
+  },
+  sprite::SpriteBundle,
+};
+use bevy_ecs_ldtk::{app::LdtkEntityAppExt, GridCoords, LdtkEntity};
+
+use super::{
+  daycycle::{DayTick, NewDayEvent},
+  grass::OccupiesGround,
+  interface::{Interface, Notice},
+  inventory::{Inventory, ItemData, Stack},
+  market::{Market, PriceUpdate},
+  player::{in_reach, Player},
+  wallet::Wallet,
+};
+
+// Holds items dropped in by the player until they are sold overnight
+#[derive(Default, Component)]
+pub struct ShippingBin {
+  contents: Vec<Stack>,
+}
+
+#[derive(Bundle, LdtkEntity)]
+struct ShippingBinBundle {
+  bin: ShippingBin,
+  ground: OccupiesGround,
+  #[grid_coords]
+  coords: GridCoords,
+  #[sprite_bundle("shipping_bin.png")]
+  sprite: SpriteBundle,
+  body: RigidBody,
+  collider: Collider,
+}
+
+impl Default for ShippingBinBundle {
+  fn default() -> Self {
+    Self {
+      bin: ShippingBin::default(),
+      ground: OccupiesGround,
+      coords: GridCoords::default(),
+      sprite: SpriteBundle::default(),
+      body: RigidBody::Static,
+      collider: Collider::rectangle(14., 14.),
+    }
+  }
+}
+
+fn deposit(
+  items: ItemData,
+  market: Res<Market>,
+  interface: Res<Interface>,
+  mouse: Res<ButtonInput<MouseButton>>,
+  mut q_player: Query<(&GlobalTransform, &mut Inventory), With<Player>>,
+  mut q_bins: Query<(&GridCoords, &mut ShippingBin)>,
+  mut ev_notice: EventWriter<Notice>,
+) {
+  if !mouse.just_pressed(MouseButton::Left) {
+    return;
+  }
+  let target = interface.cursor_grid_coords();
+  let Some((_, mut bin)) = q_bins.iter_mut().find(|(c, _)| **c == target)
+  else {
+    return;
+  };
+  let Ok((player, mut inventory)) = q_player.get_single_mut() else {
+    return;
+  };
+  if !in_reach(player, target) {
+    return;
+  }
+  let Some(info) = inventory
+    .selected()
+    .and_then(|stack| items.info(&stack.item))
+  else {
+    return;
+  };
+  if info.price == 0 {
+    ev_notice.send(Notice(format!("{} can't be sold", info.name)));
+    return;
+  }
+  if let Some(stack) = inventory.take_selected() {
+    info!("Shipping {} {}", stack.amount, stack.item.0);
+    ev_notice.send(Notice(format!(
+      "Shipping {} {} at {}g each",
+      stack.amount,
+      info.name,
+      market.price(info)
+    )));
+    bin.contents.push(stack);
+  }
+}
+
+fn sell(
+  items: ItemData,
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut q_bins: Query<&mut ShippingBin>,
+  mut market: ResMut<Market>,
+  mut wallet: ResMut<Wallet>,
+  mut ev_notice: EventWriter<Notice>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  let mut earned = 0;
+  for mut bin in &mut q_bins {
+    for stack in bin.contents.drain(..) {
+      let price = items.info(&stack.item).map_or(0, |info| market.price(info));
+      earned += price * stack.amount;
+      market.ship(&stack.item, stack.amount);
+    }
+  }
+  if earned > 0 {
+    wallet.earn(earned);
+    ev_notice.send(Notice(format!("Shipped goods sold for {earned}g")));
+  }
+}
+
+pub struct ShippingPlugin;
+
+impl Plugin for ShippingPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .register_ldtk_entity_for_layer::<ShippingBinBundle>(
+        "entities",
+        "shipping_bin",
+      )
+      .add_systems(Update, (deposit, sell.after(DayTick).before(PriceUpdate)));
+  }
+}
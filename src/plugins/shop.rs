@@ -0,0 +1,261 @@
+use avian2d::prelude::{Collider, RigidBody};
+use bevy::{
+  app::{App, Plugin, Startup, Update},
+  asset::{
+    io::Reader, Asset, AssetApp, AssetLoader, AssetServer, Assets,
+    AsyncReadExt, Handle, LoadContext,
+  },
+  input::ButtonInput,
+  prelude::{
+    Bundle, Commands, Component, GlobalTransform, KeyCode, MouseButton, Query,
+    Res, ResMut, Resource, With,
+  },
+  reflect::TypePath,
+  sprite::SpriteBundle,
+};
+use bevy_ecs_ldtk::{app::LdtkEntityAppExt, GridCoords, LdtkEntity};
+use bevy_egui::{egui, EguiContexts};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+  daycycle::{DayCycle, Season, Weekday},
+  grass::OccupiesGround,
+  interface::{Interface, Notice},
+  inventory::{Item, ItemData},
+  market::Market,
+  player::{in_reach, Player},
+  wallet::Trader,
+};
+
+#[derive(Deserialize)]
+pub struct ShopEntry {
+  pub item: Item,
+  pub price: u32,
+  // Seasons the item is sold in, every season when empty
+  #[serde(default)]
+  pub seasons: Vec<Season>,
+  // Weekdays the item is sold on, every day when empty
+  #[serde(default)]
+  pub weekdays: Vec<Weekday>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct ShopStock {
+  stock: Vec<ShopEntry>,
+}
+
+#[derive(Resource)]
+struct Shop {
+  stock: Handle<ShopStock>,
+  open: bool,
+}
+
+#[derive(Default, Component)]
+pub struct Shopkeeper;
+
+#[derive(Bundle, LdtkEntity)]
+struct ShopBundle {
+  shopkeeper: Shopkeeper,
+  ground: OccupiesGround,
+  #[grid_coords]
+  coords: GridCoords,
+  #[sprite_bundle("shop.png")]
+  sprite: SpriteBundle,
+  body: RigidBody,
+  collider: Collider,
+}
+
+impl Default for ShopBundle {
+  fn default() -> Self {
+    Self {
+      shopkeeper: Shopkeeper,
+      ground: OccupiesGround,
+      coords: GridCoords::default(),
+      sprite: SpriteBundle::default(),
+      body: RigidBody::Static,
+      collider: Collider::rectangle(14., 14.),
+    }
+  }
+}
+
+#[derive(Default)]
+struct ShopStockLoader;
+
+#[derive(Debug, Error)]
+enum ShopStockLoaderError {
+  #[error("Could not read shop stock: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Could not parse shop stock: {0}")]
+  Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for ShopStockLoader {
+  type Asset = ShopStock;
+  type Settings = ();
+  type Error = ShopStockLoaderError;
+
+  async fn load<'a>(
+    &'a self,
+    reader: &'a mut Reader<'_>,
+    _settings: &'a (),
+    _load_context: &'a mut LoadContext<'_>,
+  ) -> Result<Self::Asset, Self::Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+    Ok(ron::de::from_bytes(&bytes)?)
+  }
+
+  fn extensions(&self) -> &[&str] {
+    &["shop.ron"]
+  }
+}
+
+fn setup(mut commands: Commands, server: Res<AssetServer>) {
+  commands.insert_resource(Shop {
+    stock: server.load("shop.ron"),
+    open: false,
+  });
+}
+
+fn open_shop(
+  interface: Res<Interface>,
+  mouse: Res<ButtonInput<MouseButton>>,
+  q_player: Query<&GlobalTransform, With<Player>>,
+  q_shopkeepers: Query<&GridCoords, With<Shopkeeper>>,
+  mut shop: ResMut<Shop>,
+) {
+  if !mouse.just_pressed(MouseButton::Left) {
+    return;
+  }
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  let target = interface.cursor_grid_coords();
+  if in_reach(player, target)
+    && q_shopkeepers.iter().any(|coords| *coords == target)
+  {
+    shop.open = true;
+  }
+}
+
+// Closes the shop once the player walks away from it
+fn close_shop(
+  kbd: Res<ButtonInput<KeyCode>>,
+  q_player: Query<&GlobalTransform, With<Player>>,
+  q_shopkeepers: Query<&GridCoords, With<Shopkeeper>>,
+  mut shop: ResMut<Shop>,
+) {
+  if !shop.open {
+    return;
+  }
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  if kbd.just_pressed(KeyCode::Escape)
+    || !q_shopkeepers.iter().any(|coords| in_reach(player, *coords))
+  {
+    shop.open = false;
+  }
+}
+
+fn shop_window(
+  mut contexts: EguiContexts,
+  mut shop: ResMut<Shop>,
+  stocks: Res<Assets<ShopStock>>,
+  items: ItemData,
+  day: Res<DayCycle>,
+  market: Res<Market>,
+  trader: Trader,
+) {
+  if !shop.open {
+    return;
+  }
+  let Trader {
+    mut wallet,
+    mut q_inventory,
+    mut ev_notice,
+  } = trader;
+  let (Some(stock), Ok(mut inventory)) =
+    (stocks.get(&shop.stock), q_inventory.get_single_mut())
+  else {
+    return;
+  };
+
+  let mut open = true;
+  egui::Window::new("Shop")
+    .open(&mut open)
+    .collapsible(false)
+    .resizable(false)
+    .show(contexts.ctx_mut(), |ui| {
+      ui.label(format!("{:?}, {}", day.weekday(), day.date()));
+      ui.label(format!("Money: {}g", wallet.money));
+      ui.separator();
+      egui::Grid::new("shop_stock").striped(true).show(ui, |ui| {
+        for entry in stock.available(day.season(), day.weekday()) {
+          let name = items
+            .info(&entry.item)
+            .map_or(entry.item.0.as_str(), |info| info.name.as_str());
+          ui.label(name);
+          ui.label(format!("{}g", entry.price));
+          let affordable = wallet.money >= entry.price;
+          if ui
+            .add_enabled(affordable, egui::Button::new("Buy"))
+            .clicked()
+          {
+            let max_stack = items.max_stack(&entry.item);
+            if !inventory.fits(&entry.item, 1, max_stack) {
+              ev_notice.send(Notice("Inventory full".to_owned()));
+            } else if wallet.spend(entry.price) {
+              inventory.add(entry.item.clone(), 1, max_stack);
+            }
+          }
+          ui.end_row();
+        }
+      });
+      ui.separator();
+      ui.collapsing("Market prices", |ui| {
+        egui::Grid::new("market_prices")
+          .striped(true)
+          .show(ui, |ui| {
+            let Some(registry) = items.registry() else {
+              return;
+            };
+            for info in registry.items().filter(|info| info.price > 0) {
+              let change = (market.factor(info) - 1.) * 100.;
+              ui.label(&info.name);
+              ui.label(format!("{}g", market.price(info)));
+              ui.label(format!("{change:+.0}%"));
+              ui.end_row();
+            }
+          });
+      });
+    });
+  shop.open &= open;
+}
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+  fn build(&self, app: &mut App) {
+    app
+      .init_asset::<ShopStock>()
+      .init_asset_loader::<ShopStockLoader>()
+      .register_ldtk_entity_for_layer::<ShopBundle>("entities", "shop")
+      .add_systems(Startup, setup)
+      .add_systems(Update, (open_shop, close_shop, shop_window));
+  }
+}
+
+impl ShopStock {
+  pub fn available(
+    &self,
+    season: Season,
+    weekday: Weekday,
+  ) -> impl Iterator<Item = &ShopEntry> {
+    self.stock.iter().filter(move |entry| {
+      (entry.seasons.is_empty() || entry.seasons.contains(&season))
+        && (entry.weekdays.is_empty() || entry.weekdays.contains(&weekday))
+    })
+  }
+}
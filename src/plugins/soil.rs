@@ -0,0 +1,129 @@
+use bevy::{
+  app::{App, Plugin, Update},
+  prelude::{
+    Commands, Component, Entity, EventReader, IntoSystemConfigs, Query, With,
+    Without,
+  },
+};
+use serde::Deserialize;
+
+use super::{
+  daycycle::{DayTick, NewDayEvent},
+  grass::Arability,
+};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+pub struct Nutrients {
+  pub nitrogen: f32,
+  pub phosphorus: f32,
+  pub potassium: f32,
+}
+
+#[derive(Component)]
+pub struct Soil {
+  pub nutrients: Nutrients,
+  // Species harvested last on this tile, used for the rotation bonus
+  pub last_crop: Option<String>,
+}
+
+// Marks a tile that was already fertilized today
+#[derive(Component)]
+pub struct Fertilized;
+
+#[derive(Deserialize)]
+pub struct SoilModel {
+  // Nutrients restored by one use of the fertilizer
+  pub fertilizer: Nutrients,
+  // Harvest multiplier when the soil lacks every nutrient a crop needs
+  pub depleted_harvest: f32,
+  // Harvest multiplier when the crop differs from the previous one
+  pub rotation_bonus: f32,
+}
+
+fn gen(
+  mut commands: Commands,
+  q_uninit: Query<(Entity, &Arability), Without<Soil>>,
+) {
+  for (entity, arability) in &q_uninit {
+    commands.entity(entity).insert(Soil {
+      nutrients: Nutrients::splat(0.5 + 0.5 * arability.0),
+      last_crop: None,
+    });
+  }
+}
+
+fn reset_fertilized(
+  mut ev_newday: EventReader<NewDayEvent>,
+  q_fertilized: Query<Entity, With<Fertilized>>,
+  mut commands: Commands,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  for entity in &q_fertilized {
+    commands.entity(entity).remove::<Fertilized>();
+  }
+}
+
+pub struct SoilPlugin;
+
+impl Plugin for SoilPlugin {
+  fn build(&self, app: &mut App) {
+    app.add_systems(Update, (gen, reset_fertilized.after(DayTick)));
+  }
+}
+
+impl Nutrients {
+  pub fn splat(v: f32) -> Self {
+    Self {
+      nitrogen: v,
+      phosphorus: v,
+      potassium: v,
+    }
+  }
+
+  // How well these nutrients cover `need`, from 0 (not at all) to 1
+  pub fn satisfaction(&self, need: &Nutrients) -> f32 {
+    let ratio = |have: f32, need: f32| {
+      if need <= 0. {
+        1.
+      } else {
+        (have / need).clamp(0., 1.)
+      }
+    };
+    ratio(self.nitrogen, need.nitrogen)
+      .min(ratio(self.phosphorus, need.phosphorus))
+      .min(ratio(self.potassium, need.potassium))
+  }
+
+  pub fn deplete(&mut self, by: &Nutrients) {
+    self.nitrogen = (self.nitrogen - by.nitrogen).max(0.);
+    self.phosphorus = (self.phosphorus - by.phosphorus).max(0.);
+    self.potassium = (self.potassium - by.potassium).max(0.);
+  }
+
+  pub fn restore(&mut self, by: &Nutrients) {
+    self.nitrogen = (self.nitrogen + by.nitrogen).min(1.);
+    self.phosphorus = (self.phosphorus + by.phosphorus).min(1.);
+    self.potassium = (self.potassium + by.potassium).min(1.);
+  }
+}
+
+impl SoilModel {
+  pub fn harvest_factor(
+    &self,
+    soil: &Soil,
+    species: &str,
+    need: &Nutrients,
+  ) -> f32 {
+    let satisfaction = soil.nutrients.satisfaction(need);
+    let factor =
+      self.depleted_harvest + (1. - self.depleted_harvest) * satisfaction;
+    match &soil.last_crop {
+      Some(last) if last != species => factor * self.rotation_bonus,
+      _ => factor,
+    }
+  }
+}
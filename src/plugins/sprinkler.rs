@@ -0,0 +1,107 @@
+use bevy::{
+  app::{App, Plugin, Update},
+  asset::AssetServer,
+  prelude::{
+    default, Bundle, Commands, Component, EventReader, IntoSystemConfigs,
+    Query, Res, Transform, With,
+  },
+  sprite::SpriteBundle,
+};
+use bevy_ecs_ldtk::GridCoords;
+use serde::Deserialize;
+
+use super::{
+  daycycle::{DayTick, NewDayEvent},
+  grass::{Farmland, Growth, Watered},
+  world::{tile_center, WorldIndex},
+};
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum SprinklerPattern {
+  Plus,
+  Square,
+  BigSquare,
+}
+
+#[derive(Component)]
+pub struct Sprinkler {
+  pub pattern: SprinklerPattern,
+}
+
+#[derive(Bundle)]
+pub struct SprinklerBundle {
+  sprinkler: Sprinkler,
+  coords: GridCoords,
+  sprite: SpriteBundle,
+}
+
+fn water(
+  mut ev_newday: EventReader<NewDayEvent>,
+  world_index: Res<WorldIndex>,
+  q_sprinklers: Query<(&Sprinkler, &GridCoords)>,
+  q_farmland: Query<(), With<Farmland>>,
+  mut commands: Commands,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  for (sprinkler, coords) in &q_sprinklers {
+    for (dx, dy) in sprinkler.pattern.offsets() {
+      let target = GridCoords {
+        x: coords.x + dx,
+        y: coords.y + dy,
+      };
+      if let Some(entity) = world_index
+        .get_entity(target)
+        .filter(|&entity| q_farmland.contains(entity))
+      {
+        commands.entity(entity).insert(Watered);
+      }
+    }
+  }
+}
+
+pub struct SprinklerPlugin;
+
+impl Plugin for SprinklerPlugin {
+  fn build(&self, app: &mut App) {
+    // Watering has to land after growth clears yesterday's water
+    app.add_systems(Update, water.after(DayTick).after(Growth));
+  }
+}
+
+impl SprinklerPattern {
+  pub fn offsets(&self) -> Vec<(i32, i32)> {
+    let radius = match self {
+      SprinklerPattern::Plus => {
+        return vec![(0, 1), (0, -1), (-1, 0), (1, 0)];
+      }
+      SprinklerPattern::Square => 1,
+      SprinklerPattern::BigSquare => 2,
+    };
+    (-radius..=radius)
+      .flat_map(|dx| (-radius..=radius).map(move |dy| (dx, dy)))
+      .filter(|&offset| offset != (0, 0))
+      .collect()
+  }
+}
+
+impl SprinklerBundle {
+  pub fn new(
+    pattern: SprinklerPattern,
+    coords: GridCoords,
+    server: &AssetServer,
+  ) -> Self {
+    Self {
+      sprinkler: Sprinkler { pattern },
+      coords,
+      sprite: SpriteBundle {
+        texture: server.load("sprinkler.png"),
+        transform: Transform::from_translation(tile_center(coords).extend(4.)),
+        ..default()
+      },
+    }
+  }
+}
@@ -1,77 +1,397 @@
+use std::collections::HashMap;
+
 use bevy::{
-  app::{App, Plugin},
-  asset::{AssetServer, Handle},
-  ecs::system::EntityCommands,
-  prelude::{Image, Mut, Resource},
+  app::{App, Plugin, Update},
+  asset::AssetServer,
+  ecs::system::{EntityCommands, SystemParam},
+  hierarchy::DespawnRecursiveExt,
+  input::ButtonInput,
+  prelude::{
+    Commands, Component, Entity, Event, EventWriter, GlobalTransform,
+    MouseButton, Query, Res, ResMut, Resource, With,
+  },
 };
+use bevy_ecs_ldtk::GridCoords;
+use serde::Deserialize;
 
-use super::grass::{FarmStage, Farmland, Watered};
+use super::{
+  controls::Direction,
+  crops::{CropData, CropYield},
+  daycycle::DayCycle,
+  drops::DropEvent,
+  energy::Energy,
+  grass::{FarmTileItem, Farmland, Harvest, Occupied, Watered},
+  interface::{Interface, Notice, Pointer},
+  inventory::{Inventory, Item, ItemData},
+  machines::{Machine, MachineBundle},
+  player::{in_reach, Player},
+  soil::Fertilized,
+  sprinkler::SprinklerBundle,
+  world::{tile_center, TileType, WaterBoundaries, WorldIndex},
+};
 
-#[derive(Resource)]
+const WATERING_CAN_CAPACITY: u32 = 20;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
 pub enum Tool {
   Cultivate,
   Plant,
   Water,
+  Fertilize,
+  Sprinkler,
+  Machine,
   Harvest,
 }
 
-impl Tool {
-  pub fn name(&self) -> &'static str {
-    match self {
-      Tool::Cultivate => "cultivate",
-      Tool::Plant => "plant",
-      Tool::Water => "water",
-      Tool::Harvest => "harvest",
-    }
-  }
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+pub enum ToolTier {
+  #[default]
+  Basic,
+  Copper,
+  Iron,
+  Gold,
+}
 
-  pub fn texture(&self, server: &AssetServer) -> Handle<Image> {
-    server.load(&format!("ui/{}.png", self.name()))
-  }
+#[derive(Resource, Default)]
+pub struct ToolTiers(HashMap<Tool, ToolTier>);
 
-  pub fn cycle(&mut self) {
-    *self = match self {
-      Tool::Cultivate => Tool::Plant,
-      Tool::Plant => Tool::Water,
-      Tool::Water => Tool::Harvest,
-      Tool::Harvest => Tool::Cultivate,
-    };
+#[derive(Resource)]
+pub struct WateringCan {
+  pub charges: u32,
+  pub capacity: u32,
+}
+
+// Item a sprinkler or machine was placed from, given back when picked up
+#[derive(Component)]
+pub struct Placed(pub Item);
+
+#[derive(SystemParam)]
+pub struct ToolContext<'w> {
+  pub crops: CropData<'w>,
+  pub items: ItemData<'w>,
+  pub day: Res<'w, DayCycle>,
+  pub server: Res<'w, AssetServer>,
+  pub tiers: Res<'w, ToolTiers>,
+  pub can: ResMut<'w, WateringCan>,
+  pub energy: ResMut<'w, Energy>,
+  pub ev_harvest: EventWriter<'w, HarvestEvent>,
+  pub ev_notice: EventWriter<'w, Notice>,
+}
+
+#[derive(Event)]
+pub struct HarvestEvent {
+  pub tile: Entity,
+  pub item: Item,
+  pub amount: u32,
+}
+
+impl Tool {
+  pub fn upgradable(&self) -> bool {
+    matches!(self, Tool::Cultivate | Tool::Water | Tool::Harvest)
   }
-  pub fn rev_cycle(&mut self) {
-    *self = match self {
-      Tool::Cultivate => Tool::Harvest,
-      Tool::Plant => Tool::Cultivate,
-      Tool::Water => Tool::Plant,
-      Tool::Harvest => Tool::Water,
-    };
+
+  pub fn energy_cost(&self) -> u32 {
+    match self {
+      Tool::Cultivate => 4,
+      Tool::Plant => 1,
+      Tool::Water => 2,
+      Tool::Fertilize => 2,
+      Tool::Sprinkler => 1,
+      Tool::Machine => 1,
+      Tool::Harvest => 1,
+    }
   }
 
+  // Uses the held `item` on one tile, consuming it if it is a seed, fertilizer,
+  // sprinkler or machine
   pub fn activate(
     &self,
+    item: &Item,
+    inventory: &mut Inventory,
     mut target: EntityCommands,
-    farmland: Option<Mut<'_, Farmland>>,
+    mut tile: FarmTileItem,
+    ctx: &mut ToolContext,
   ) {
-    match self {
+    if !ctx.energy.can_afford(self.energy_cost()) {
+      return;
+    }
+
+    let crops = &ctx.crops;
+    let used = match self {
       Tool::Cultivate => {
-        target.insert(Farmland(FarmStage::Empty));
-      }
-      Tool::Plant => {
-        if let Some(mut farmland) = farmland {
-          farmland.0.plant();
+        if tile.cultivable && tile.farmland.is_none() && !tile.occupied {
+          target.insert(Farmland::default());
+          true
+        } else {
+          false
         }
       }
+      Tool::Plant => match (tile.farmland, crops.planted_from(item)) {
+        (Some(mut farmland), Some((id, species))) if farmland.can_plant() => {
+          let season = ctx.day.season();
+          if species.seasons.contains(&season) {
+            inventory.take(item, 1) && farmland.plant(id)
+          } else {
+            ctx.ev_notice.send(Notice(format!(
+              "{} can't be planted in {season:?}",
+              species.name
+            )));
+            false
+          }
+        }
+        _ => false,
+      },
       Tool::Water => {
-        target.insert(Watered);
+        if tile.farmland.is_some() && !tile.watered && ctx.can.pour() {
+          target.insert(Watered);
+          true
+        } else {
+          false
+        }
       }
-      Tool::Harvest => todo!(),
+      Tool::Fertilize => match crops.registry().filter(|_| !tile.fertilized) {
+        Some(registry) if inventory.take(item, 1) => {
+          tile.soil.nutrients.restore(&registry.soil.fertilizer);
+          target.insert(Fertilized);
+          true
+        }
+        _ => false,
+      },
+      Tool::Sprinkler => {
+        let pattern = ctx.items.info(item).and_then(|info| info.sprinkler);
+        match pattern {
+          Some(pattern)
+            if tile.farmland.is_none()
+              && !tile.occupied
+              && inventory.take(item, 1) =>
+          {
+            let sprinkler =
+              SprinklerBundle::new(pattern, *tile.coords, &ctx.server);
+            target.insert(Occupied);
+            target.commands().spawn((sprinkler, Placed(item.clone())));
+            true
+          }
+          _ => false,
+        }
+      }
+      Tool::Machine => {
+        let texture = ctx
+          .items
+          .info(item)
+          .filter(|info| info.machine.is_some())
+          .map(|info| info.icon.clone());
+        match texture {
+          Some(texture)
+            if tile.farmland.is_none()
+              && !tile.occupied
+              && inventory.take(item, 1) =>
+          {
+            let machine = MachineBundle::new(
+              item.clone(),
+              &texture,
+              *tile.coords,
+              &ctx.server,
+            );
+            target.insert(Occupied);
+            target.commands().spawn((machine, Placed(item.clone())));
+            true
+          }
+          _ => false,
+        }
+      }
+      Tool::Harvest => match tile.harvest(crops) {
+        Some(Harvest::Crop(yields)) => {
+          for CropYield { item, amount } in yields {
+            ctx.ev_harvest.send(HarvestEvent {
+              tile: target.id(),
+              item,
+              amount,
+            });
+          }
+          true
+        }
+        Some(Harvest::Cleared) => true,
+        None => false,
+      },
     };
+
+    if used {
+      ctx.energy.spend(self.energy_cost());
+    }
   }
 }
 
+fn refill_can(
+  items: ItemData,
+  interface: Res<Interface>,
+  mouse: Res<ButtonInput<MouseButton>>,
+  world_index: Res<WorldIndex>,
+  boundaries: Res<WaterBoundaries>,
+  q_player: Query<(&GlobalTransform, &Inventory), With<Player>>,
+  mut can: ResMut<WateringCan>,
+) {
+  if !mouse.pressed(MouseButton::Left) {
+    return;
+  }
+  let Ok((player, inventory)) = q_player.get_single() else {
+    return;
+  };
+  let held = inventory
+    .selected()
+    .and_then(|stack| items.tool(&stack.item));
+  if held != Some(Tool::Water) {
+    return;
+  }
+  let coords = interface.cursor_grid_coords();
+  if in_reach(player, coords)
+    && world_index.get_type(coords) == Some(TileType::Water)
+    && boundaries.contains(&coords)
+  {
+    can.charges = can.capacity;
+  }
+}
+
+// Right clicking a placed sprinkler or machine breaks it back into its item
+fn pick_up(
+  mut ctx: ToolContext,
+  pointer: Pointer,
+  world_index: Res<WorldIndex>,
+  q_player: Query<&GlobalTransform, With<Player>>,
+  q_placed: Query<(Entity, &GridCoords, &Placed, Option<&Machine>)>,
+  mut ev_drop: EventWriter<DropEvent>,
+  mut commands: Commands,
+) {
+  let Some(target) = pointer.clicked(MouseButton::Right) else {
+    return;
+  };
+  let Ok(player) = q_player.get_single() else {
+    return;
+  };
+  if !in_reach(player, target) {
+    return;
+  }
+  let Some((entity, coords, placed, machine)) =
+    q_placed.iter().find(|(_, coords, _, _)| **coords == target)
+  else {
+    return;
+  };
+  let Placed(item) = placed;
+  if machine.is_some_and(Machine::busy) {
+    let name = ctx.items.info(item).map_or(&item.0, |info| &info.name);
+    ctx
+      .ev_notice
+      .send(Notice(format!("Empty the {name} first")));
+    return;
+  }
+
+  commands.entity(entity).despawn_recursive();
+  if let Some(tile) = world_index.get_entity(*coords) {
+    commands.entity(tile).remove::<Occupied>();
+  }
+  ev_drop.send(DropEvent {
+    at: tile_center(*coords),
+    item: item.clone(),
+    amount: 1,
+  });
+}
+
 pub struct ToolsPlugin;
 
 impl Plugin for ToolsPlugin {
   fn build(&self, app: &mut App) {
-    app.insert_resource(Tool::Cultivate);
+    app
+      .init_resource::<ToolTiers>()
+      .insert_resource(WateringCan {
+        charges: WATERING_CAN_CAPACITY,
+        capacity: WATERING_CAN_CAPACITY,
+      })
+      .add_systems(Update, (refill_can, pick_up))
+      .add_event::<HarvestEvent>();
+  }
+}
+
+impl WateringCan {
+  pub fn pour(&mut self) -> bool {
+    if self.charges > 0 {
+      self.charges -= 1;
+      true
+    } else {
+      false
+    }
+  }
+}
+
+impl ToolTier {
+  // Largest area any tier covers, in tiles
+  pub const MAX_AREA: usize = 25;
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ToolTier::Basic => "Basic",
+      ToolTier::Copper => "Copper",
+      ToolTier::Iron => "Iron",
+      ToolTier::Gold => "Gold",
+    }
+  }
+
+  pub fn next(&self) -> Option<ToolTier> {
+    match self {
+      ToolTier::Basic => Some(ToolTier::Copper),
+      ToolTier::Copper => Some(ToolTier::Iron),
+      ToolTier::Iron => Some(ToolTier::Gold),
+      ToolTier::Gold => None,
+    }
+  }
+
+  // Tiles affected when aiming at `target`, the line tier extends away from
+  // the player along `facing`
+  pub fn area(
+    &self,
+    target: GridCoords,
+    facing: &Direction,
+  ) -> Vec<GridCoords> {
+    let at = |dx: i32, dy: i32| GridCoords {
+      x: target.x + dx,
+      y: target.y + dy,
+    };
+    let square = |radius: i32| -> Vec<GridCoords> {
+      (-radius..=radius)
+        .flat_map(|dx| (-radius..=radius).map(move |dy| at(dx, dy)))
+        .collect()
+    };
+    match self {
+      ToolTier::Basic => vec![target],
+      ToolTier::Copper => {
+        let (dx, dy) = facing.offset();
+        (0..3).map(|i| at(dx * i, dy * i)).collect()
+      }
+      ToolTier::Iron => square(1),
+      ToolTier::Gold => square(2),
+    }
+  }
+}
+
+impl ToolTiers {
+  pub fn get(&self, tool: &Tool) -> ToolTier {
+    self.0.get(tool).copied().unwrap_or_default()
+  }
+
+  pub fn upgrade(&mut self, tool: &Tool) -> bool {
+    match self.get(tool).next().filter(|_| tool.upgradable()) {
+      Some(tier) => {
+        self.0.insert(*tool, tier);
+        true
+      }
+      None => false,
+    }
+  }
+
+  pub fn area(
+    &self,
+    tool: &Tool,
+    target: GridCoords,
+    facing: &Direction,
+  ) -> Vec<GridCoords> {
+    self.get(tool).area(target, facing)
   }
 }
@@ -0,0 +1,46 @@
+use bevy::{
+  app::{App, Plugin},
+  ecs::system::SystemParam,
+  prelude::{EventWriter, Query, ResMut, Resource, With},
+};
+
+use super::{interface::Notice, inventory::Inventory, player::Player};
+
+#[derive(Resource, Default)]
+pub struct Wallet {
+  pub money: u32,
+  // Total ever earned, spending does not lower it
+  pub earned: u32,
+}
+
+// Money and inventory of the player, for windows that trade with them
+#[derive(SystemParam)]
+pub struct Trader<'w, 's> {
+  pub wallet: ResMut<'w, Wallet>,
+  pub q_inventory: Query<'w, 's, &'static mut Inventory, With<Player>>,
+  pub ev_notice: EventWriter<'w, Notice>,
+}
+
+pub struct WalletPlugin;
+
+impl Plugin for WalletPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<Wallet>();
+  }
+}
+
+impl Wallet {
+  pub fn earn(&mut self, amount: u32) {
+    self.money = self.money.saturating_add(amount);
+    self.earned = self.earned.saturating_add(amount);
+  }
+
+  pub fn spend(&mut self, amount: u32) -> bool {
+    if self.money >= amount {
+      self.money -= amount;
+      true
+    } else {
+      false
+    }
+  }
+}
@@ -0,0 +1,309 @@
+use bevy::{
+  app::{App, Plugin, Update},
+  color::Color,
+  log::info,
+  math::Vec2,
+  prelude::{
+    default, Commands, Component, DetectChanges, Entity, EventReader,
+    IntoSystemConfigs, Query, Ref, Res, ResMut, Resource, Transform, With,
+    Without,
+  },
+  sprite::{Sprite, SpriteBundle},
+  time::{Time, Timer, TimerMode},
+};
+use bevy_ecs_ldtk::GridCoords;
+use rand::{thread_rng, Rng};
+
+use super::{
+  camera::MainCamera,
+  daycycle::{DayCycle, DayTick, NewDayEvent, Season},
+  grass::{Farmland, Growth, Watered},
+};
+
+// Chance for each crop to be destroyed during a storm
+const STORM_DAMAGE_CHANCE: f64 = 0.05;
+// Average lightning strikes per second during a storm
+const LIGHTNING_RATE: f64 = 0.1;
+// Flash brightness fading per second
+const FLASH_FADE: f32 = 3.;
+// Area around the camera particles are spawned in
+const PARTICLE_AREA: Vec2 = Vec2::new(420., 260.);
+const PARTICLE_Z: f32 = 50.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+  Sunny,
+  Cloudy,
+  Rain,
+  Storm,
+  Snow,
+}
+
+#[derive(Resource)]
+pub struct Forecast {
+  pub today: Weather,
+  pub tomorrow: Weather,
+  // Extra ambient light from a lightning strike
+  pub flash: f32,
+}
+
+struct ParticleStyle {
+  // Particles spawned per second
+  rate: f32,
+  velocity: Vec2,
+  size: Vec2,
+  color: Color,
+  lifetime: f32,
+}
+
+#[derive(Component)]
+struct WeatherParticle {
+  velocity: Vec2,
+  lifetime: Timer,
+}
+
+// Rolls tomorrow's weather, making today what was forecast yesterday
+fn roll_weather(
+  day: Res<DayCycle>,
+  mut forecast: ResMut<Forecast>,
+  mut ev_newday: EventReader<NewDayEvent>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  let season = day.season();
+  let mut rng = thread_rng();
+  forecast.today = forecast.tomorrow;
+  // Yesterday's forecast may not fit a season that just began
+  if Weather::weight(season, forecast.today) == 0 {
+    forecast.today = Weather::roll(season, &mut rng);
+  }
+  forecast.tomorrow = Weather::roll(season, &mut rng);
+  info!(
+    "Weather today: {:?}, tomorrow: {:?}",
+    forecast.today, forecast.tomorrow
+  );
+}
+
+// Rain waters farmland each morning and any cultivated while it rains
+fn rain(
+  forecast: Res<Forecast>,
+  mut ev_newday: EventReader<NewDayEvent>,
+  q_farmland: Query<(Entity, Ref<Farmland>), Without<Watered>>,
+  mut commands: Commands,
+) {
+  let new_day = !ev_newday.is_empty();
+  ev_newday.clear();
+  if !forecast.today.waters() {
+    return;
+  }
+  for (entity, farmland) in &q_farmland {
+    if new_day || farmland.is_added() {
+      commands.entity(entity).insert(Watered);
+    }
+  }
+}
+
+fn storm_damage(
+  forecast: Res<Forecast>,
+  mut ev_newday: EventReader<NewDayEvent>,
+  mut q_farmland: Query<(&mut Farmland, &GridCoords)>,
+) {
+  if ev_newday.is_empty() {
+    return;
+  }
+  ev_newday.clear();
+
+  if forecast.today != Weather::Storm {
+    return;
+  }
+  let mut rng = thread_rng();
+  for (mut farmland, coords) in &mut q_farmland {
+    if farmland.crop.is_some() && rng.gen_bool(STORM_DAMAGE_CHANCE) {
+      info!("Storm destroyed the crop at {coords:?}");
+      farmland.crop = None;
+    }
+  }
+}
+
+fn lightning(time: Res<Time>, mut forecast: ResMut<Forecast>) {
+  let delta = time.delta_seconds();
+  forecast.flash = (forecast.flash - FLASH_FADE * delta).max(0.);
+  if forecast.today == Weather::Storm
+    && thread_rng().gen_bool((LIGHTNING_RATE * delta as f64).min(1.))
+  {
+    forecast.flash = 1.;
+  }
+}
+
+fn spawn_particles(
+  time: Res<Time>,
+  forecast: Res<Forecast>,
+  q_camera: Query<&Transform, With<MainCamera>>,
+  mut commands: Commands,
+) {
+  let Some(style) = forecast.today.particles() else {
+    return;
+  };
+  let Ok(camera) = q_camera.get_single() else {
+    return;
+  };
+  let mut rng = thread_rng();
+  let expected = style.rate * time.delta_seconds();
+  let count = expected as u32 + rng.gen_bool(expected.fract() as f64) as u32;
+  for _ in 0..count {
+    let offset = Vec2::new(
+      rng.gen_range(-0.5..0.5) * PARTICLE_AREA.x,
+      rng.gen_range(-0.5..0.5) * PARTICLE_AREA.y,
+    );
+    let at = camera.translation.truncate() + offset;
+    commands.spawn((
+      WeatherParticle {
+        velocity: style.velocity,
+        lifetime: Timer::from_seconds(style.lifetime, TimerMode::Once),
+      },
+      SpriteBundle {
+        sprite: Sprite {
+          color: style.color,
+          custom_size: Some(style.size),
+          ..default()
+        },
+        transform: Transform::from_translation(at.extend(PARTICLE_Z)),
+        ..default()
+      },
+    ));
+  }
+}
+
+fn update_particles(
+  time: Res<Time>,
+  mut q_particles: Query<(Entity, &mut WeatherParticle, &mut Transform)>,
+  mut commands: Commands,
+) {
+  for (entity, mut particle, mut transform) in &mut q_particles {
+    if particle.lifetime.tick(time.delta()).finished() {
+      commands.entity(entity).despawn();
+      continue;
+    }
+    transform.translation +=
+      (particle.velocity * time.delta_seconds()).extend(0.);
+  }
+}
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+  fn build(&self, app: &mut App) {
+    app.init_resource::<Forecast>().add_systems(
+      Update,
+      (
+        roll_weather.after(DayTick),
+        rain.after(roll_weather).after(Growth),
+        storm_damage.after(roll_weather),
+        lightning,
+        spawn_particles,
+        update_particles,
+      ),
+    );
+  }
+}
+
+impl Default for Forecast {
+  fn default() -> Self {
+    Self {
+      today: Weather::Sunny,
+      tomorrow: Weather::roll(Season::Spring, &mut thread_rng()),
+      flash: 0.,
+    }
+  }
+}
+
+impl Weather {
+  const ALL: [Weather; 5] = [
+    Weather::Sunny,
+    Weather::Cloudy,
+    Weather::Rain,
+    Weather::Storm,
+    Weather::Snow,
+  ];
+
+  // Relative chance of the weather in `season`
+  pub fn weight(season: Season, weather: Weather) -> u32 {
+    match (season, weather) {
+      (Season::Spring, Weather::Sunny) => 45,
+      (Season::Spring, Weather::Cloudy) => 25,
+      (Season::Spring, Weather::Rain) => 25,
+      (Season::Spring, Weather::Storm) => 5,
+      (Season::Summer, Weather::Sunny) => 55,
+      (Season::Summer, Weather::Cloudy) => 15,
+      (Season::Summer, Weather::Rain) => 15,
+      (Season::Summer, Weather::Storm) => 15,
+      (Season::Autumn, Weather::Sunny) => 35,
+      (Season::Autumn, Weather::Cloudy) => 30,
+      (Season::Autumn, Weather::Rain) => 30,
+      (Season::Autumn, Weather::Storm) => 5,
+      (Season::Winter, Weather::Sunny) => 30,
+      (Season::Winter, Weather::Cloudy) => 30,
+      (Season::Winter, Weather::Snow) => 40,
+      _ => 0,
+    }
+  }
+
+  pub fn roll(season: Season, rng: &mut impl Rng) -> Self {
+    let total: u32 = Self::ALL.iter().map(|&w| Self::weight(season, w)).sum();
+    let mut pick = rng.gen_range(0..total);
+    for weather in Self::ALL {
+      let weight = Self::weight(season, weather);
+      if pick < weight {
+        return weather;
+      }
+      pick -= weight;
+    }
+    Weather::Sunny
+  }
+
+  pub fn waters(&self) -> bool {
+    matches!(self, Weather::Rain | Weather::Storm)
+  }
+
+  // Share of the sunlight that gets through
+  pub fn light(&self) -> f32 {
+    match self {
+      Weather::Sunny => 1.,
+      Weather::Cloudy => 0.85,
+      Weather::Rain => 0.7,
+      Weather::Storm => 0.55,
+      Weather::Snow => 0.9,
+    }
+  }
+
+  fn particles(&self) -> Option<ParticleStyle> {
+    let rain = Color::srgba(0.6, 0.7, 1., 0.6);
+    match self {
+      Weather::Sunny | Weather::Cloudy => None,
+      Weather::Rain => Some(ParticleStyle {
+        rate: 120.,
+        velocity: Vec2::new(-40., -300.),
+        size: Vec2::new(1., 5.),
+        color: rain,
+        lifetime: 0.4,
+      }),
+      Weather::Storm => Some(ParticleStyle {
+        rate: 300.,
+        velocity: Vec2::new(-120., -400.),
+        size: Vec2::new(1., 6.),
+        color: rain,
+        lifetime: 0.4,
+      }),
+      Weather::Snow => Some(ParticleStyle {
+        rate: 40.,
+        velocity: Vec2::new(-10., -30.),
+        size: Vec2::new(2., 2.),
+        color: Color::srgba(1., 1., 1., 0.8),
+        lifetime: 2.,
+      }),
+    }
+  }
+}
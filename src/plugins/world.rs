@@ -4,6 +4,7 @@ use avian2d::prelude::{Collider, RigidBody};
 use bevy::{
   app::{App, Plugin, Startup, Update},
   asset::AssetServer,
+  math::Vec2,
   prelude::{
     default, Bundle, Children, Commands, Component, Entity, EventReader, Query,
     Res, ResMut, Resource, With, Without,
@@ -39,7 +40,10 @@ impl Plugin for WorldPlugin {
         TileType::Water.index(),
       )
       .add_systems(Startup, setup_world)
-      .add_systems(Update, (cache_water_coundaries, spawn_boundaries, cache_index))
+      .add_systems(
+        Update,
+        (cache_water_coundaries, spawn_boundaries, cache_index),
+      )
       .insert_resource(LevelSelection::index(0))
       .insert_resource(WaterBoundaries {
         boundaries: HashSet::new(),
@@ -66,8 +70,9 @@ struct WaterBundle {
   water: Water,
 }
 
+// Water tiles next to land, where the player can reach the water
 #[derive(Resource)]
-struct WaterBoundaries {
+pub struct WaterBoundaries {
   boundaries: HashSet<GridCoords>,
 }
 
@@ -180,6 +185,16 @@ impl TryFrom<usize> for TileType {
   }
 }
 
+pub fn tile_center(GridCoords { x, y }: GridCoords) -> Vec2 {
+  Vec2::new(x as f32, y as f32) * 16. + 8.
+}
+
+impl WaterBoundaries {
+  pub fn contains(&self, coords: &GridCoords) -> bool {
+    self.boundaries.contains(coords)
+  }
+}
+
 impl WorldIndex {
   fn set(
     &mut self,